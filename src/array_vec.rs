@@ -0,0 +1,259 @@
+#![allow(unsafe_code)]
+
+use core::{
+    fmt,
+    mem::{ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr,
+};
+
+/// A `Vec` with a fixed capacity of `N` that lives entirely inline, without
+/// ever allocating.
+pub struct ArrayVec<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { buf: [const { MaybeUninit::uninit() }; N], len: 0 }
+    }
+
+    /// # Errors
+    /// Hands `val` back when the `ArrayVec` is full.
+    pub const fn push(&mut self, val: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(val);
+        }
+        self.buf[self.len].write(val);
+        self.len += 1;
+        Ok(())
+    }
+
+    pub const fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { self.buf[self.len].assume_init_read() })
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    #[must_use]
+    pub const fn as_slice(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.buf.as_ptr().cast(), self.len) }
+    }
+
+    #[must_use]
+    pub const fn as_slice_mut(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.buf.as_mut_ptr().cast(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_slice_mut()) };
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_slice_mut()
+    }
+}
+
+impl<T, const N: usize> core::ops::Index<usize> for ArrayVec<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.as_slice()[index]
+    }
+}
+
+impl<T, const N: usize> core::ops::IndexMut<usize> for ArrayVec<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.as_slice_mut()[index]
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for ArrayVec<T, N>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type IntoIter = IntoIter<T, N>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let vec = ManuallyDrop::new(self);
+        let buf = unsafe { ptr::read(&raw const vec.buf) };
+
+        IntoIter { buf, current: 0, end: vec.len }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type IntoIter = core::slice::Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayVec<T, N> {
+    type IntoIter = core::slice::IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct IntoIter<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    current: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current == self.end {
+            return None;
+        }
+        let val = unsafe { self.buf[self.current].assume_init_read() };
+        self.current += 1;
+        Some(val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.current == self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(unsafe { self.buf[self.end].assume_init_read() })
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {
+    fn len(&self) -> usize {
+        self.end - self.current
+    }
+}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let remaining = ptr::slice_from_raw_parts_mut(
+            unsafe { self.buf.as_mut_ptr().add(self.current).cast::<T>() },
+            self.len(),
+        );
+        self.current = self.end;
+        // Only the inline `current..end` range is still initialized. Dropping
+        // it as one slice also drops the rest if an element panics.
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+#[test]
+fn test_push_pop() {
+    let mut items = ArrayVec::<i32, 4>::new();
+    for i in 0..4 {
+        assert_eq!(items.push(i), Ok(()));
+    }
+    assert!(items.is_full());
+    assert_eq!(items.push(4), Err(4));
+    assert_eq!(items.len(), 4);
+    assert_eq!(items[1], 1);
+    assert_eq!(items.as_slice(), &[0, 1, 2, 3]);
+
+    assert_eq!(items.pop(), Some(3));
+    assert_eq!(items.push(5), Ok(()));
+    assert!(items.into_iter().eq([0, 1, 2, 5]));
+
+    let mut items = ArrayVec::<i32, 0>::new();
+    assert_eq!(items.push(0), Err(0));
+    assert_eq!(items.pop(), None);
+
+    let allocations = crate::test_alloc::count_allocations(|| {
+        let mut items = ArrayVec::<u64, 64>::new();
+        while items.push(0).is_ok() {}
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_drop() {
+    use alloc::{rc::Rc, string::String};
+
+    let marker = Rc::new(());
+    let mut items = ArrayVec::<_, 4>::new();
+    for _ in 0..3 {
+        items.push(Rc::clone(&marker)).unwrap();
+    }
+    drop(items);
+    assert_eq!(Rc::strong_count(&marker), 1);
+
+    let mut items = ArrayVec::<_, 4>::new();
+    for _ in 0..4 {
+        items.push(Rc::clone(&marker)).unwrap();
+    }
+    let mut iter = items.into_iter();
+    iter.next();
+    iter.next_back();
+    assert_eq!(Rc::strong_count(&marker), 3);
+    drop(iter);
+    assert_eq!(Rc::strong_count(&marker), 1);
+
+    let mut strings = ArrayVec::<String, 4>::new();
+    strings.push(String::from("lorem")).unwrap();
+    strings.push(String::from("ipsum")).unwrap();
+    assert_eq!(strings.pop().as_deref(), Some("ipsum"));
+    assert_eq!(strings.push(String::from("dolor")), Ok(()));
+}
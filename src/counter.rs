@@ -0,0 +1,135 @@
+#![forbid(unsafe_code)]
+
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{hasher::PlainBuildHasher, HashMap, Vec};
+
+/// Counts how often each distinct item was added.
+pub struct Counter<T, S = PlainBuildHasher> {
+    counts: HashMap<T, usize, S>,
+}
+
+impl<T> Counter<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_hasher(PlainBuildHasher::new())
+    }
+}
+
+impl<T, S> Counter<T, S> {
+    #[must_use]
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self { counts: HashMap::with_hasher(hasher) }
+    }
+
+    /// The number of distinct items.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// The `k` items with the highest counts, highest first. Items with equal
+    /// counts come in no particular order.
+    pub fn most_common(&self, k: usize) -> Vec<(&T, usize)> {
+        let mut counts: Vec<_> = self.iter().collect();
+        counts.sort_unstable_by_key(|&(_, count)| core::cmp::Reverse(count));
+        counts.truncate(k);
+        counts
+    }
+}
+
+impl<T, S> Counter<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    pub fn add_n(&mut self, item: T, n: usize) {
+        *self.counts.entry(item).or_insert(0) += n;
+    }
+
+    /// How often `item` was added, which is 0 if it never was.
+    pub fn count<Q>(&self, item: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+}
+
+impl<T, S> Extend<T> for Counter<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T, S> FromIterator<T> for Counter<T, S>
+where
+    T: Hash + Eq,
+    S: Default + BuildHasher,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::default();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T, S> Default for Counter<T, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<T> fmt::Debug for Counter<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.counts.fmt(f)
+    }
+}
+
+#[test]
+fn test_counter() {
+    let mut counter: Counter<_> = "the cat saw the dog and the dog ran".split(' ').collect();
+    assert_eq!(counter.len(), 6);
+    assert_eq!(counter.count("the"), 3);
+    assert_eq!(counter.count("dog"), 2);
+    assert_eq!(counter.count("bird"), 0);
+    assert_eq!(counter.most_common(2).as_slice(), &[(&"the", 3), (&"dog", 2)]);
+
+    counter.add_n("cat", 5);
+    counter.add("dog");
+    counter.add("dog");
+    assert_eq!(counter.count("dog"), 4);
+    assert_eq!(counter.most_common(3).as_slice(), &[(&"cat", 6), (&"dog", 4), (&"the", 3)]);
+    assert_eq!(counter.most_common(100).len(), 6);
+    assert!(Counter::<i32>::new().most_common(3).is_empty());
+}
@@ -0,0 +1,137 @@
+#![forbid(unsafe_code)]
+
+use alloc::sync::Arc;
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{hasher::PlainBuildHasher, HashMap};
+
+/// A `HashMap` behind an `Arc` that's cloned on the first mutation, so
+/// snapshots are cheap and only diverge once edited.
+pub struct CowMap<K, V, S = PlainBuildHasher> {
+    inner: Arc<HashMap<K, V, S>>,
+}
+
+impl<K, V> CowMap<K, V> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from(HashMap::new())
+    }
+}
+
+impl<K, V, S> CowMap<K, V, S> {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Shares the current contents without copying them.
+    #[must_use]
+    pub fn snapshot(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<K, V, S> CowMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.inner.get(key)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.inner.contains_key(key)
+    }
+
+    /// Copies the map first if a snapshot still shares it.
+    pub fn insert(&mut self, key: K, val: V) -> Option<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+        S: Clone,
+    {
+        Arc::make_mut(&mut self.inner).insert(key, val)
+    }
+
+    /// Copies the map first if a snapshot still shares it and `key` is present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q> + Clone,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+        S: Clone,
+    {
+        if !self.contains_key(key) {
+            return None;
+        }
+        Arc::make_mut(&mut self.inner).remove(key)
+    }
+}
+
+impl<K, V, S> From<HashMap<K, V, S>> for CowMap<K, V, S> {
+    fn from(map: HashMap<K, V, S>) -> Self {
+        Self { inner: Arc::new(map) }
+    }
+}
+
+impl<K, V, S> Default for CowMap<K, V, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self::from(HashMap::default())
+    }
+}
+
+impl<K, V> fmt::Debug for CowMap<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+#[test]
+fn test_snapshots() {
+    let mut map = CowMap::new();
+    map.insert("lorem", 1);
+    map.insert("ipsum", 2);
+
+    let snapshot = map.snapshot();
+    let untouched = map.snapshot();
+    assert!(Arc::ptr_eq(&snapshot.inner, &map.inner));
+
+    map.insert("lorem", 10);
+    assert_eq!(map.remove("ipsum"), Some(2));
+    assert!(!Arc::ptr_eq(&snapshot.inner, &map.inner));
+    assert_eq!(map.get("lorem"), Some(&10));
+    assert_eq!(map.len(), 1);
+
+    assert_eq!(snapshot.get("lorem"), Some(&1));
+    assert_eq!(snapshot.get("ipsum"), Some(&2));
+    assert!(Arc::ptr_eq(&snapshot.inner, &untouched.inner));
+
+    let mut shared = snapshot.snapshot();
+    assert_eq!(shared.remove("dolor"), None);
+    assert!(Arc::ptr_eq(&snapshot.inner, &shared.inner));
+}
@@ -5,30 +5,86 @@ use core::{
     borrow::Borrow,
     fmt,
     hash::{BuildHasher, Hash},
-    iter, mem,
+    iter::{self, FusedIterator},
+    mem,
 };
 
 use crate::{hasher::PlainBuildHasher, Vec};
 
 pub struct HashMap<K, V, S = PlainBuildHasher> {
-    buckets: Box<[Bucket<K, V>]>,
+    buckets: Vec<Bucket<K, V>>,
+    len: usize,
+    // Emptied nodes of removed entries, reused by later inserts.
+    node_pool: Vec<Box<Node<K, V>>>,
     hasher: S,
 }
 
 impl<K, V> HashMap<K, V> {
+    /// Doesn't allocate, so it can be used to initialize constants.
     #[must_use]
-    pub fn new() -> Self {
-        Self { buckets: Box::from([]), hasher: PlainBuildHasher::default() }
+    pub const fn new() -> Self {
+        Self::with_hasher(PlainBuildHasher::new())
+    }
+
+    /// # Panics
+    /// Panics with "capacity overflow" if the bucket count would overflow.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        K: Hash + Eq,
+    {
+        let mut map = Self::new();
+        map.reserve(capacity);
+        map
+    }
+
+    /// Sizes the buckets so that `capacity` entries average at most `max_load`
+    /// entries per bucket. Lower loads trade memory for shorter chains.
+    ///
+    /// # Panics
+    /// Panics unless `0.0 < max_load <= 1.0`, and with "capacity overflow" if
+    /// the bucket count would overflow.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn with_capacity_and_load(capacity: usize, max_load: f64) -> Self
+    where
+        K: Hash + Eq,
+    {
+        assert!(
+            max_load > 0.0 && max_load <= 1.0,
+            "max_load was {max_load}, but must be in (0.0, 1.0]"
+        );
+        let required = capacity as f64 / max_load;
+        assert!(required < usize::MAX as f64, "capacity overflow");
+        let mut bucket_count = required as usize;
+        if (bucket_count as f64) < required {
+            bucket_count += 1;
+        }
+        let bucket_count = bucket_count.checked_next_power_of_two().expect("capacity overflow");
+
+        let mut map = Self::new();
+        if capacity != 0 {
+            map.rehash(bucket_count.max(Self::START_CAPACITY));
+        }
+        map
     }
 }
 
 impl<K, V, S> HashMap<K, V, S> {
+    const COMPACT_ITER_THRESHOLD: usize = 1024;
     const MAX_BUCKET_LEN: usize = 6;
+    const MAX_POOL_LEN: usize = 32;
     const START_CAPACITY: usize = 8;
 
+    /// Doesn't allocate, so it can be used to initialize constants.
     #[must_use]
-    pub fn len(&self) -> usize {
-        self.buckets.iter().map(Bucket::len).sum()
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self { buckets: Vec::new(), len: 0, node_pool: Vec::new(), hasher }
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
     }
 
     #[must_use]
@@ -36,8 +92,28 @@ impl<K, V, S> HashMap<K, V, S> {
         self.buckets.len()
     }
 
+    /// Summarizes how entries are spread across the buckets, to help diagnose
+    /// a poorly distributed hash.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn load_stats(&self) -> LoadStats {
+        let mut stats = LoadStats { min: usize::MAX, max: 0, mean: 0.0, empty: 0 };
+        for bucket in &self.buckets {
+            let len = bucket.len();
+            stats.min = stats.min.min(len);
+            stats.max = stats.max.max(len);
+            stats.empty += usize::from(len == 0);
+        }
+        if self.buckets.is_empty() {
+            stats.min = 0;
+        } else {
+            stats.mean = self.len as f64 / self.buckets.len() as f64;
+        }
+        stats
+    }
+
     #[must_use]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
@@ -51,6 +127,22 @@ impl<K, V, S> HashMap<K, V, S> {
         self.into_iter()
     }
 
+    /// Iterates like `iter`, but for maps of at least 1024 entries first
+    /// gathers every entry into a contiguous scratch `Vec` and yields from
+    /// that. Smaller maps just use `iter`.
+    ///
+    /// That costs one temporary `len`-sized `Vec<(&K, &V)>` allocation, two
+    /// pointers per entry, plus a full walk of the node chains up front. In
+    /// return, the iteration itself reads memory sequentially.
+    pub fn iter_compact(&self) -> impl Iterator<Item = (&K, &V)> {
+        let (compact, plain) = if self.len >= Self::COMPACT_ITER_THRESHOLD {
+            (Some(self.iter().collect::<Vec<_>>()), None)
+        } else {
+            (None, Some(self.iter()))
+        };
+        compact.into_iter().flatten().chain(plain.into_iter().flatten())
+    }
+
     pub fn keys(&self) -> impl Iterator<Item = &K> {
         self.iter().map(|entry| entry.0)
     }
@@ -59,9 +151,101 @@ impl<K, V, S> HashMap<K, V, S> {
         self.iter().map(|entry| entry.1)
     }
 
+    /// Like `iter`, but boxed so the type can be named, e.g. in a struct field
+    /// or as the return type of a trait method.
+    #[must_use]
+    pub fn boxed_iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        Box::new(self.iter())
+    }
+
+    /// Like `keys`, but boxed so the type can be named.
+    #[must_use]
+    pub fn boxed_keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(self.keys())
+    }
+
+    /// Like `values`, but boxed so the type can be named.
+    #[must_use]
+    pub fn boxed_values(&self) -> Box<dyn Iterator<Item = &V> + '_> {
+        Box::new(self.values())
+    }
+
+    pub fn fold_values<A>(&self, init: A, mut f: impl FnMut(A, &K, &V) -> A) -> A {
+        self.iter().fold(init, |acc, (key, val)| f(acc, key, val))
+    }
+
     pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
         self.iter_mut().map(|entry| entry.1)
     }
+
+    /// Rebuilds the map with every key passed through `f`, keeping the values
+    /// and the hasher.
+    ///
+    /// If several keys map to the same new key, only one entry survives: the
+    /// one visited last, in the map's unspecified iteration order.
+    pub fn map_keys<L, F>(self, mut f: F) -> HashMap<L, V, S>
+    where
+        L: Hash + Eq,
+        F: FnMut(K) -> L,
+        S: BuildHasher,
+    {
+        let Self { buckets, len, node_pool: _, hasher } = self;
+        let mut map = HashMap::with_hasher(hasher);
+        map.reserve(len);
+        for (key, val) in (IntoIter { buckets: buckets.into_iter(), chain: None, len }) {
+            map.insert(f(key), val);
+        }
+        map
+    }
+
+    /// Lazily removes and yields the entries for which `f` returns true.
+    ///
+    /// Each entry is examined at most once. Dropping the iterator early keeps
+    /// every entry that wasn't yielded yet, and the buckets are never shrunk.
+    pub fn extract_if<'a, F>(&'a mut self, mut f: F) -> impl Iterator<Item = (K, V)> + 'a
+    where
+        F: FnMut(&K, &mut V) -> bool + 'a,
+    {
+        let (mut bucket, mut skip) = (0, 0);
+        iter::from_fn(move || {
+            while bucket < self.buckets.len() {
+                if let Some(node) = self.buckets[bucket].extract_first(&mut skip, &mut f) {
+                    self.len -= 1;
+                    return Some(self.recycle_node(node));
+                }
+                (bucket, skip) = (bucket + 1, 0);
+            }
+            None
+        })
+    }
+
+    /// Keeps only the entries for which `f` returns true, returning the
+    /// removed ones. The buckets are never shrunk.
+    pub fn retain_returning(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) -> Vec<(K, V)> {
+        self.extract_if(|key, val| !f(key, val)).collect()
+    }
+
+    #[allow(clippy::unnecessary_box_returns)]
+    fn new_node(&mut self, key: K, val: V) -> Box<Node<K, V>> {
+        match self.node_pool.pop() {
+            Some(mut node) => {
+                node.entry = Some((key, val));
+                node
+            }
+            None => Box::new(Node::new(key, val)),
+        }
+    }
+
+    /// Moves the entry out of `node`, keeping the emptied node in the pool
+    /// unless the pool is full.
+    fn recycle_node(&mut self, mut node: Box<Node<K, V>>) -> (K, V) {
+        debug_assert!(node.next.is_none());
+        let entry = node.take_entry();
+        if self.node_pool.len() < Self::MAX_POOL_LEN {
+            self.node_pool.push(node);
+        }
+        entry
+    }
 }
 
 impl<K, V, S> HashMap<K, V, S>
@@ -69,16 +253,107 @@ where
     K: Hash + Eq,
     S: BuildHasher,
 {
+    /// Makes room for at least `additional` more entries.
+    ///
+    /// The map still grows whenever a bucket fills up, so this keeps an average
+    /// of at most half an entry per bucket, which makes that very unlikely.
+    ///
+    /// # Panics
+    /// Panics with "capacity overflow" if the bucket count would overflow.
+    pub fn reserve(&mut self, additional: usize) {
+        if additional == 0 {
+            return;
+        }
+        let required = self
+            .len
+            .checked_add(additional)
+            .and_then(|entries| entries.checked_mul(2))
+            .and_then(usize::checked_next_power_of_two)
+            .expect("capacity overflow");
+        if required > self.buckets.len() {
+            self.rehash(required.max(Self::START_CAPACITY));
+        }
+    }
+
+    /// Rebuilds the map around `hasher`, for example to switch to a
+    /// DoS-resistant hasher once an attack is suspected.
+    ///
+    /// Every entry is placed again using the new hasher, reusing its node
+    /// allocation. The bucket count is sized for the current length, so a map
+    /// that used to be much larger also shrinks.
+    ///
+    /// # Panics
+    /// Panics with "capacity overflow" if the bucket count would overflow.
+    pub fn rehash_with<S2: BuildHasher>(self, hasher: S2) -> HashMap<K, V, S2> {
+        let Self { buckets, len, node_pool: _, hasher: _ } = self;
+        let mut map = HashMap::with_hasher(hasher);
+        map.reserve(len);
+        for node in buckets.into_iter().flatten() {
+            let bucket = map.get_bucket_unchecked(node.key());
+            map.buckets[bucket].push_node(node);
+        }
+        map.len = len;
+        map
+    }
+
     pub fn insert(&mut self, key: K, val: V) -> Option<(K, V)> {
         if self.buckets.is_empty() {
             self.grow();
         }
         let bucket = self.get_bucket_unchecked(&key);
-        let prev_entry = self.buckets[bucket].push(key, val);
-        if self.buckets[bucket].len() == Self::MAX_BUCKET_LEN {
-            self.grow();
+        if let Some(node) = self.buckets[bucket].get_node_mut(&key) {
+            return node.entry.replace((key, val));
+        }
+        self.insert_absent(key, val);
+        None
+    }
+
+    /// Inserts `val` only if `key` isn't already present, returning whether it
+    /// was inserted along with a reference to the stored value.
+    pub fn insert_if_absent(&mut self, key: K, val: V) -> (bool, &mut V) {
+        if !self.contains_key(&key) {
+            return (true, self.insert_absent(key, val).1);
+        }
+        let Some(val) = self.get_mut(&key) else { unreachable!() };
+        (false, val)
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        if !self.contains_key(&key) {
+            return Entry::Vacant(VacantEntry { map: self, key });
+        }
+        let Some(val) = self.get_mut(&key) else { unreachable!() };
+        Entry::Occupied(val)
+    }
+
+    /// Looks up `key` by reference, only converting it into an owned `K` if
+    /// a vacant entry gets inserted into.
+    pub fn entry_ref<'a, 'q, Q>(&'a mut self, key: &'q Q) -> EntryRef<'a, 'q, K, Q, V, S>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return EntryRef::Vacant(VacantEntryRef { map: self, key });
         }
-        prev_entry
+        let Some(val) = self.get_mut(key) else { unreachable!() };
+        EntryRef::Occupied(val)
+    }
+
+    /// Returns the value for `key`, computing and inserting it on a miss.
+    ///
+    /// `compute` gets the map itself, so it can recurse through
+    /// `get_or_compute` for memoization. If it inserts `key` itself, that
+    /// value is kept and returned instead of the computed one.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self, &K) -> V) -> V
+    where
+        V: Clone,
+    {
+        if let Some(val) = self.get(&key) {
+            return val.clone();
+        }
+        let val = compute(self, &key);
+        self.insert_if_absent(key, val).1.clone()
     }
 
     pub fn get<Q>(&self, key: &Q) -> Option<&V>
@@ -90,6 +365,15 @@ where
         self.buckets[bucket].get(key)
     }
 
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let bucket = &self.buckets[self.get_bucket(key)?];
+        bucket.into_iter().find(|entry| entry.0.borrow() == key)
+    }
+
     pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
     where
         K: Borrow<Q>,
@@ -113,7 +397,9 @@ where
         Q: Hash + Eq + ?Sized,
     {
         let bucket = self.get_bucket(key)?;
-        self.buckets[bucket].remove(key)
+        let node = self.buckets[bucket].remove(key)?;
+        self.len -= 1;
+        Some(self.recycle_node(node))
     }
 
     pub fn contains_key<Q>(&self, key: &Q) -> bool
@@ -130,7 +416,7 @@ where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        if self.is_empty() {
+        if self.buckets.is_empty() {
             return None;
         }
         Some(self.get_bucket_unchecked(key))
@@ -147,15 +433,96 @@ where
         bucket as usize
     }
 
+    /// Checks a sample of up to 64 entries for signs of a `Hash` impl that
+    /// disagrees with `Eq`, which make lookups silently miss. Does nothing in
+    /// release builds.
+    ///
+    /// # Panics
+    /// Panics in debug builds with a diagnostic if a sampled key no longer
+    /// hashes to the bucket it's stored in, or if another key compares equal to
+    /// it but is stored in a different bucket.
+    pub fn validate_hash_eq(&self) {
+        const SAMPLE_LEN: usize = 64;
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let entries = || {
+            self.buckets
+                .iter()
+                .enumerate()
+                .flat_map(|(index, bucket)| bucket.into_iter().map(move |(key, _)| (index, key)))
+        };
+        for (index, key) in entries().step_by(self.len.div_ceil(SAMPLE_LEN).max(1)) {
+            let hashed = self.get_bucket_unchecked(key);
+            assert!(
+                hashed == index,
+                "a key stored in bucket {index} now hashes to bucket {hashed}: its hash changed \
+                 after insertion or `Hash` isn't deterministic"
+            );
+            for (other_index, other) in entries() {
+                assert!(
+                    other_index == index || other != key,
+                    "keys stored in buckets {index} and {other_index} compare equal: equal keys \
+                     must have equal hashes"
+                );
+            }
+        }
+    }
+
+    /// # Panics
+    /// Panics if an entry isn't in the bucket its hash dictates, if a bucket
+    /// holds the same key twice, or if `len` doesn't match the stored entries.
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn check_invariants(&self) {
+        // Chains are owned through `Box`, so they can't form cycles.
+        let mut counted = 0;
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            for (nth, (key, _)) in bucket.into_iter().enumerate() {
+                assert_eq!(
+                    self.get_bucket_unchecked(key),
+                    index,
+                    "entry stored in bucket {index} hashes to a different bucket"
+                );
+                assert!(
+                    bucket.into_iter().skip(nth + 1).all(|(other, _)| other != key),
+                    "bucket {index} holds the same key twice"
+                );
+                counted += 1;
+            }
+        }
+        assert_eq!(counted, self.len, "len doesn't match the number of stored entries");
+    }
+
+    /// Inserts an entry whose key isn't in the map yet, returning references
+    /// to the stored key and value.
+    pub(crate) fn insert_absent(&mut self, key: K, val: V) -> (&K, &mut V) {
+        if self.buckets.is_empty() {
+            self.grow();
+        }
+        let mut bucket = self.get_bucket_unchecked(&key);
+        // Grow before pushing so the returned reference stays valid.
+        if self.buckets[bucket].len() + 1 == Self::MAX_BUCKET_LEN {
+            self.grow();
+            bucket = self.get_bucket_unchecked(&key);
+        }
+        let node = self.new_node(key, val);
+        self.len += 1;
+        self.buckets[bucket].push_node(node).entry_mut()
+    }
+
     fn grow(&mut self) {
         if self.buckets.is_empty() {
             return self.buckets =
                 iter::repeat_with(Bucket::new).take(Self::START_CAPACITY).collect();
         }
-        let new_buckets = iter::repeat_with(Bucket::new).take(self.buckets.len() * 2).collect();
+        self.rehash(self.buckets.len() * 2);
+    }
+
+    fn rehash(&mut self, bucket_count: usize) {
+        let new_buckets = iter::repeat_with(Bucket::new).take(bucket_count).collect();
         let old_buckets = mem::replace(&mut self.buckets, new_buckets);
-        for node in Vec::from(old_buckets).into_iter().flatten() {
-            let bucket = self.get_bucket_unchecked(&node.key);
+        for node in old_buckets.into_iter().flatten() {
+            let bucket = self.get_bucket_unchecked(node.key());
             self.buckets[bucket].push_node(node);
         }
     }
@@ -167,6 +534,8 @@ where
     S: BuildHasher,
 {
     fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
         for (key, val) in iter {
             self.insert(key, val);
         }
@@ -186,17 +555,44 @@ where
 }
 
 impl<K, V, S> IntoIterator for HashMap<K, V, S> {
+    type IntoIter = IntoIter<K, V>;
     type Item = (K, V);
 
-    type IntoIter = impl Iterator<Item = (K, V)>;
-
     fn into_iter(self) -> Self::IntoIter {
-        Vec::from(self.buckets)
-            .into_iter()
-            .flat_map(|bucket| bucket.into_iter().map(|node| (node.key, node.val)))
+        IntoIter { buckets: self.buckets.into_iter(), chain: None, len: self.len }
     }
 }
 
+pub struct IntoIter<K, V> {
+    buckets: crate::vec::IntoIter<Bucket<K, V>>,
+    // The rest of the bucket currently being drained.
+    chain: Option<Box<Node<K, V>>>,
+    len: usize,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(mut node) = self.chain.take() {
+                self.chain = node.next.take();
+                self.len -= 1;
+                return Some(node.take_entry());
+            }
+            self.chain = self.buckets.next()?.head;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
 impl<'a, K, V, S> IntoIterator for &'a HashMap<K, V, S> {
     type Item = (&'a K, &'a V);
 
@@ -222,7 +618,24 @@ where
     S: Default,
 {
     fn default() -> Self {
-        Self { buckets: Box::from([]), hasher: S::default() }
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> Clone for HashMap<K, V, S>
+where
+    K: Clone,
+    V: Clone,
+    S: Clone,
+{
+    /// The clone starts with an empty node pool.
+    fn clone(&self) -> Self {
+        Self {
+            buckets: self.buckets.clone(),
+            len: self.len,
+            node_pool: Vec::new(),
+            hasher: self.hasher.clone(),
+        }
     }
 }
 
@@ -236,13 +649,164 @@ where
     }
 }
 
+pub enum Entry<'a, K, V, S> {
+    Occupied(&'a mut V),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+pub struct VacantEntry<'a, K, V, S> {
+    map: &'a mut HashMap<K, V, S>,
+    key: K,
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+        self.or_insert_with_key(|_| default())
+    }
+
+    /// Like `or_insert_with`, but `default` gets to see the key that is about
+    /// to be stored.
+    pub fn or_insert_with_key(self, default: impl FnOnce(&K) -> V) -> &'a mut V {
+        match self {
+            Self::Occupied(val) => val,
+            Self::Vacant(entry) => {
+                let val = default(&entry.key);
+                entry.insert(val)
+            }
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S> {
+    #[must_use]
+    pub const fn key(&self) -> &K {
+        &self.key
+    }
+
+    #[must_use]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    pub fn insert(self, val: V) -> &'a mut V
+    where
+        K: Hash + Eq,
+        S: BuildHasher,
+    {
+        self.map.insert_absent(self.key, val).1
+    }
+}
+
+pub enum EntryRef<'a, 'q, K, Q: ?Sized, V, S> {
+    Occupied(&'a mut V),
+    Vacant(VacantEntryRef<'a, 'q, K, Q, V, S>),
+}
+
+pub struct VacantEntryRef<'a, 'q, K, Q: ?Sized, V, S> {
+    map: &'a mut HashMap<K, V, S>,
+    key: &'q Q,
+}
+
+impl<'a, 'q, K, Q, V, S> EntryRef<'a, 'q, K, Q, V, S>
+where
+    K: Hash + Eq + From<&'q Q>,
+    Q: ?Sized,
+    S: BuildHasher,
+{
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Self::Occupied(val) => val,
+            Self::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, 'q, K, Q, V, S> VacantEntryRef<'a, 'q, K, Q, V, S>
+where
+    Q: ?Sized,
+{
+    #[must_use]
+    pub const fn key(&self) -> &'q Q {
+        self.key
+    }
+
+    pub fn insert(self, val: V) -> &'a mut V
+    where
+        K: Hash + Eq + From<&'q Q>,
+        S: BuildHasher,
+    {
+        self.map.insert_absent(K::from(self.key), val).1
+    }
+}
+
+/// Bucket chain lengths, as returned by `HashMap::load_stats`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    /// The number of buckets without any entries.
+    pub empty: usize,
+}
+
 struct Bucket<K, V> {
     head: Option<Box<Node<K, V>>>,
 }
 struct Node<K, V> {
     next: Option<Box<Node<K, V>>>,
-    key: K,
-    val: V,
+    // Only `None` while the node waits in the pool, never in a bucket.
+    entry: Option<(K, V)>,
+}
+
+impl<K, V> Node<K, V> {
+    const fn new(key: K, val: V) -> Self {
+        Self { next: None, entry: Some((key, val)) }
+    }
+
+    fn key(&self) -> &K {
+        self.entry().0
+    }
+
+    fn entry(&self) -> (&K, &V) {
+        let Some((key, val)) = &self.entry else { unreachable!("node in a bucket is empty") };
+        (key, val)
+    }
+
+    fn entry_mut(&mut self) -> (&K, &mut V) {
+        let Some((key, val)) = &mut self.entry else { unreachable!("node in a bucket is empty") };
+        (key, val)
+    }
+
+    fn take_entry(&mut self) -> (K, V) {
+        let Some(entry) = self.entry.take() else { unreachable!("node in a bucket is empty") };
+        entry
+    }
 }
 
 impl<K, V> Bucket<K, V> {
@@ -260,17 +824,12 @@ impl<K, V> Bucket<K, V> {
         len
     }
 
-    fn push(&mut self, key: K, val: V) -> Option<(K, V)> {
-        let node = self.push_node(Box::new(Node { next: None, key, val }))?;
-        Some((node.key, node.val))
-    }
-
-    fn push_node(&mut self, val: Box<Node<K, V>>) -> Option<Box<Node<K, V>>> {
+    fn push_node(&mut self, val: Box<Node<K, V>>) -> &mut Node<K, V> {
         let mut head = &mut self.head;
         while let Some(current) = head {
             head = &mut current.next;
         }
-        head.replace(val)
+        head.insert(val)
     }
 
     fn get<Q>(&self, key: &Q) -> Option<&V>
@@ -280,8 +839,9 @@ impl<K, V> Bucket<K, V> {
     {
         let mut head = &self.head;
         while let Some(current) = head {
-            if current.key.borrow() == key {
-                return Some(&current.val);
+            let (current_key, val) = current.entry();
+            if current_key.borrow() == key {
+                return Some(val);
             }
             head = &current.next;
         }
@@ -289,21 +849,51 @@ impl<K, V> Bucket<K, V> {
     }
 
     fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.get_node_mut(key).map(|node| node.entry_mut().1)
+    }
+
+    fn get_node_mut<Q>(&mut self, key: &Q) -> Option<&mut Node<K, V>>
     where
         K: Borrow<Q>,
         Q: Eq + ?Sized,
     {
         let mut head = &mut self.head;
         while let Some(current) = head {
-            if current.key.borrow() == key {
-                return Some(&mut current.val);
+            if current.key().borrow() == key {
+                return Some(current);
             }
             head = &mut current.next;
         }
         None
     }
 
-    fn remove<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    /// Unlinks the first node past the first `skip` for which `f` returns
+    /// true, counting the nodes it rejects into `skip`.
+    fn extract_first<F>(&mut self, skip: &mut usize, f: &mut F) -> Option<Box<Node<K, V>>>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut current = &mut self.head;
+        for _ in 0..*skip {
+            current = &mut current.as_mut()?.next;
+        }
+        loop {
+            let (key, val) = current.as_deref_mut()?.entry_mut();
+            if f(key, val) {
+                let mut node = current.take()?;
+                *current = node.next.take();
+                return Some(node);
+            }
+            *skip += 1;
+            current = &mut current.as_mut()?.next;
+        }
+    }
+
+    fn remove<Q>(&mut self, key: &Q) -> Option<Box<Node<K, V>>>
     where
         K: Borrow<Q>,
         Q: Eq + ?Sized,
@@ -312,10 +902,10 @@ impl<K, V> Bucket<K, V> {
         loop {
             match current {
                 None => return None,
-                Some(node) if node.key.borrow() == key => {
+                Some(node) if node.key().borrow() == key => {
                     let mut node = current.take().unwrap();
                     *current = node.next.take();
-                    return Some((node.key, node.val));
+                    return Some(node);
                 }
                 Some(node) => current = &mut node.next,
             }
@@ -323,6 +913,20 @@ impl<K, V> Bucket<K, V> {
     }
 }
 
+impl<K, V> Clone for Bucket<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut bucket = Self::new();
+        for (key, val) in self {
+            bucket.push_node(Box::new(Node::new(key.clone(), val.clone())));
+        }
+        bucket
+    }
+}
+
 impl<K, V> IntoIterator for Bucket<K, V> {
     type Item = Box<Node<K, V>>;
 
@@ -349,7 +953,7 @@ impl<'a, K, V> IntoIterator for &'a Bucket<K, V> {
         iter::from_fn(move || {
             let current = self_current?;
             self_current = current.next.as_deref();
-            Some((&current.key, &current.val))
+            Some(current.entry())
         })
     }
 }
@@ -362,9 +966,10 @@ impl<'a, K, V> IntoIterator for &'a mut Bucket<K, V> {
     fn into_iter(self) -> Self::IntoIter {
         let mut self_current = self.head.as_deref_mut();
         iter::from_fn(move || {
-            let current = mem::take(&mut self_current)?;
-            self_current = current.next.as_deref_mut();
-            Some((&current.key, &mut current.val))
+            let Node { next, entry } = mem::take(&mut self_current)?;
+            self_current = next.as_deref_mut();
+            let Some((key, val)) = entry else { unreachable!("node in a bucket is empty") };
+            Some((&*key, val))
         })
     }
 }
@@ -398,3 +1003,529 @@ fn test_growth() {
     let map = (0..1000).map(|i| (i, i * 2)).collect::<HashMap<_, _>>();
     assert_ne!(map.capacity(), HashMap::<(), ()>::START_CAPACITY);
 }
+
+#[test]
+fn test_check_invariants() {
+    let mut map = (0..100).map(|i| (i, i)).collect::<HashMap<_, _>>();
+    map.check_invariants();
+    for i in 0..50 {
+        map.remove(&i);
+    }
+    map.check_invariants();
+    HashMap::<(), ()>::new().check_invariants();
+}
+
+#[test]
+#[should_panic = "hashes to a different bucket"]
+fn test_check_invariants_misplaced() {
+    let mut map = (0..100).map(|i| (i, i)).collect::<HashMap<_, _>>();
+    let bucket = map.get_bucket_unchecked(&0);
+    let node = map.buckets[bucket].head.take().unwrap();
+    let wrong_bucket = (bucket + 1) % map.capacity();
+    map.buckets[wrong_bucket].push_node(node);
+    map.check_invariants();
+}
+
+#[test]
+#[should_panic = "len doesn't match"]
+fn test_check_invariants_len() {
+    let mut map = (0..100).map(|i| (i, i)).collect::<HashMap<_, _>>();
+    map.len += 1;
+    map.check_invariants();
+}
+
+#[test]
+fn test_insert_replaces() {
+    let mut map = HashMap::new();
+    assert_eq!(map.insert("foo", 1), None);
+    assert_eq!(map.insert("foo", 2), Some(("foo", 1)));
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get("foo"), Some(&2));
+    map.check_invariants();
+}
+
+#[test]
+fn test_insert_existing_key_doesnt_duplicate() {
+    let mut map = HashMap::new();
+    for i in 0..10 {
+        map.insert(7, i);
+    }
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.iter().count(), 1);
+    assert_eq!(map.remove(&7), Some(9));
+    assert!(!map.contains_key(&7));
+    assert!(map.is_empty());
+    map.check_invariants();
+}
+
+#[test]
+fn test_node_pool() {
+    let mut map = (0..100).map(|i| (i, i)).collect::<HashMap<_, _>>();
+    for i in 100..200 {
+        map.insert(i, i);
+        map.remove(&i);
+    }
+    let allocations = crate::test_alloc::count_allocations(|| {
+        for _ in 0..100 {
+            for i in 100..200 {
+                map.insert(i, i);
+                map.remove(&i);
+            }
+        }
+    });
+    assert_eq!(allocations, 0);
+
+    for i in 0..100 {
+        map.remove(&i);
+    }
+    assert_eq!(map.node_pool.len(), HashMap::<(), ()>::MAX_POOL_LEN);
+    map.check_invariants();
+}
+
+#[test]
+fn test_insert_if_absent() {
+    let mut map = HashMap::new();
+
+    let (inserted, val) = map.insert_if_absent("foo", 1);
+    assert!(inserted);
+    *val += 10;
+    let (inserted, val) = map.insert_if_absent("foo", 2);
+    assert!(!inserted);
+    assert_eq!(*val, 11);
+    assert_eq!(map.len(), 1);
+
+    let mut map = HashMap::new();
+    for i in 0..1000 {
+        let capacity = map.capacity();
+        let (inserted, val) = map.insert_if_absent(i, i);
+        assert!(inserted);
+        *val = -i;
+        if map.capacity() != capacity {
+            assert_eq!(map.get(&i), Some(&-i));
+        }
+    }
+    assert!((0..1000).all(|i| map.get(&i) == Some(&-i)));
+    map.check_invariants();
+}
+
+#[test]
+fn test_iter_compact() {
+    let small = (0..10).map(|i| (i, i * 2)).collect::<HashMap<_, _>>();
+    assert!(small.iter_compact().eq(small.iter()));
+
+    let large = (0..5000).map(|i| (i, i * 2)).collect::<HashMap<_, _>>();
+    assert!(large.iter_compact().eq(large.iter()));
+    assert_eq!(large.iter_compact().count(), 5000);
+}
+
+#[test]
+fn test_extract_if() {
+    let mut map = (0..100).map(|i| (i, i)).collect::<HashMap<_, _>>();
+    let capacity = map.capacity();
+
+    let mut examined = 0;
+    let mut extracted: Vec<_> = map
+        .extract_if(|_, val| {
+            examined += 1;
+            *val % 2 == 0
+        })
+        .collect();
+    extracted.sort_unstable();
+    assert_eq!(examined, 100);
+    assert!(extracted.iter().copied().eq((0..100).step_by(2).map(|i| (i, i))));
+    assert_eq!(map.len(), 50);
+    assert_eq!(map.capacity(), capacity);
+    assert!((0..100).all(|i| map.contains_key(&i) == (i % 2 == 1)));
+    map.check_invariants();
+
+    let taken: Vec<_> = map.extract_if(|_, _| true).take(5).collect();
+    assert_eq!(map.len(), 45);
+    assert!(taken.iter().all(|(key, _)| !map.contains_key(key)));
+    map.check_invariants();
+}
+
+#[test]
+fn test_const_new() {
+    const EMPTY: HashMap<&str, i32> = HashMap::new();
+    const EMPTY_WITH_HASHER: HashMap<i32, i32> = HashMap::with_hasher(PlainBuildHasher::new());
+
+    let mut map = EMPTY;
+    assert_eq!(map.capacity(), 0);
+    map.insert("foo", 1);
+    assert_eq!(map.get("foo"), Some(&1));
+    assert!(EMPTY.is_empty());
+
+    let mut map = EMPTY_WITH_HASHER;
+    map.extend((0..100).map(|i| (i, i)));
+    assert_eq!(map.len(), 100);
+}
+
+#[test]
+fn test_entry_ref_word_count() {
+    use alloc::string::String;
+
+    let words = "the cat saw the dog and the dog saw the cat".split(' ');
+    let mut map = HashMap::<String, usize>::new();
+    // Pool nodes and allocate buckets up front so only the keys allocate.
+    for key in ["a", "b", "c", "d", "e"] {
+        map.insert(String::from(key), 0);
+    }
+    map.extract_if(|_, _| true).for_each(drop);
+
+    let allocations = crate::test_alloc::count_allocations(|| {
+        for word in words.clone() {
+            *map.entry_ref(word).or_insert(0) += 1;
+        }
+    });
+    assert_eq!(allocations, 5);
+    assert_eq!(map.len(), 5);
+    assert_eq!(map.get("the"), Some(&4));
+    assert_eq!(map.get("and"), Some(&1));
+
+    let allocations = crate::test_alloc::count_allocations(|| {
+        for word in words.clone() {
+            *map.entry_ref(word).or_default() += 1;
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(map.get("the"), Some(&8));
+
+    match map.entry_ref("bird") {
+        EntryRef::Occupied(_) => panic!("bird was never inserted"),
+        EntryRef::Vacant(entry) => assert_eq!(entry.key(), "bird"),
+    }
+    assert!(!map.contains_key("bird"));
+    map.check_invariants();
+}
+
+#[test]
+fn test_reserve() {
+    let mut map = HashMap::new();
+    map.reserve(100);
+    let capacity = map.capacity();
+    assert_eq!(capacity, 256);
+    map.extend((0..100).map(|i| (i, i)));
+    assert_eq!(map.capacity(), capacity);
+
+    map.reserve(10);
+    assert_eq!(map.capacity(), capacity);
+    map.check_invariants();
+}
+
+#[test]
+fn test_fold_values() {
+    let map: HashMap<&str, i32> = [("a", 1), ("b", -4), ("c", 10), ("d", 7)].into_iter().collect();
+    let mut expected = 0;
+    for (_, val) in &map {
+        expected += val;
+    }
+    assert_eq!(map.fold_values(0, |sum, _, val| sum + val), expected);
+    assert_eq!(map.fold_values(0, |len, key, _| len + key.len()), 4);
+    assert_eq!(HashMap::<&str, i32>::new().fold_values(5, |_, _, _| unreachable!()), 5);
+}
+
+#[test]
+fn test_entry_or_insert_with_key() {
+    let mut map = HashMap::new();
+    map.insert("lorem", 0);
+
+    let mut calls = 0;
+    let mut len_of = |key: &&str| {
+        calls += 1;
+        key.len()
+    };
+    assert_eq!(*map.entry("lorem").or_insert_with_key(&mut len_of), 0);
+    assert_eq!(*map.entry("dolor sit").or_insert_with_key(&mut len_of), 9);
+    *map.entry("dolor sit").or_insert_with_key(&mut len_of) += 1;
+    assert_eq!(calls, 1);
+    assert_eq!(map.get("dolor sit"), Some(&10));
+
+    *map.entry("amet").or_default() += 2;
+    assert_eq!(map.get("amet"), Some(&2));
+    match map.entry("ipsum") {
+        Entry::Occupied(_) => panic!("ipsum was never inserted"),
+        Entry::Vacant(entry) => assert_eq!(entry.into_key(), "ipsum"),
+    }
+    assert_eq!(map.len(), 3);
+    map.check_invariants();
+}
+
+#[test]
+fn test_clone() {
+    let mut map: HashMap<_, _> = (0..100).map(|i| (i, i * 2)).collect();
+    map.remove(&3);
+    let clone = map.clone();
+    assert_eq!(clone.len(), 99);
+    assert_eq!(clone.capacity(), map.capacity());
+    assert!(clone.node_pool.is_empty());
+    assert!(map.iter().eq(clone.iter()));
+    clone.check_invariants();
+}
+
+#[test]
+fn test_into_iter_exact() {
+    fn assert_bounds<I: ExactSizeIterator + FusedIterator>(_: &I) {}
+
+    let map: HashMap<_, _> = (0..100).map(|i| (i, i * 2)).collect();
+    let mut iter = map.into_iter();
+    assert_bounds(&iter);
+    assert_eq!(iter.len(), 100);
+    iter.next();
+    assert_eq!(iter.size_hint(), (99, Some(99)));
+
+    let mut items: Vec<_> = iter.by_ref().collect();
+    assert_eq!(items.len(), 99);
+    assert_eq!(items.cap(), 99);
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+
+    items.sort_unstable();
+    assert!(items.iter().all(|&(key, val)| val == key * 2));
+    assert_eq!(HashMap::<i32, i32>::new().into_iter().len(), 0);
+}
+
+#[test]
+fn test_map_keys() {
+    let map: HashMap<i32, &str> = [(1, "one"), (2, "two"), (3, "three")].into_iter().collect();
+    let doubled = map.map_keys(|key| key * 2);
+    assert_eq!(doubled.len(), 3);
+    assert_eq!(doubled.get(&2), Some(&"one"));
+    assert_eq!(doubled.get(&6), Some(&"three"));
+    assert!(!doubled.contains_key(&1));
+    doubled.check_invariants();
+
+    let collided = doubled.map_keys(|key| key % 4);
+    assert_eq!(collided.len(), 2);
+    assert_eq!(collided.get(&0), Some(&"two"));
+    assert!(matches!(collided.get(&2), Some(&"one" | &"three")));
+    collided.check_invariants();
+}
+
+#[test]
+fn test_extend_reserves() {
+    let mut map = HashMap::new();
+    let allocations = crate::test_alloc::count_allocations(|| {
+        map.extend((0..1000).map(|i| (i, i)));
+    });
+    // One allocation per node, and the buckets only once.
+    assert_eq!(allocations, 1000 + 1);
+    assert_eq!(map.capacity(), 2048);
+    assert_eq!(map.len(), 1000);
+
+    assert!((0..1000).all(|i| map.get(&i) == Some(&i)));
+    map.check_invariants();
+}
+
+#[test]
+fn test_with_capacity() {
+    let mut map = HashMap::with_capacity(100);
+    assert_eq!(map.capacity(), 256);
+    map.extend((0..100).map(|i| (i, i)));
+    assert_eq!(map.capacity(), 256);
+    assert_eq!(HashMap::<i32, i32>::with_capacity(0).capacity(), 0);
+}
+
+#[test]
+#[should_panic = "capacity overflow"]
+fn test_with_capacity_overflow() {
+    let _ = HashMap::<i32, i32>::with_capacity(usize::MAX);
+}
+
+#[test]
+#[should_panic = "capacity overflow"]
+fn test_reserve_overflow() {
+    let mut map = HashMap::new();
+    map.insert(0, 0);
+    map.reserve(usize::MAX / 2);
+}
+
+#[test]
+fn test_rehash_with() {
+    #![allow(deprecated)]
+    use core::hash::SipHasher;
+
+    struct Seeded(u64);
+    impl BuildHasher for Seeded {
+        type Hasher = SipHasher;
+
+        fn build_hasher(&self) -> SipHasher {
+            SipHasher::new_with_keys(self.0, !self.0)
+        }
+    }
+
+    let mut map: HashMap<_, _> = (0..200).map(|i| (i, i * 2)).collect();
+    for i in 0..150 {
+        map.remove(&i);
+    }
+    let allocations = crate::test_alloc::count_allocations(|| {
+        let map = map.rehash_with(Seeded(0x1234_5678));
+        assert_eq!(map.len(), 50);
+        assert_eq!(map.capacity(), 128);
+        assert!((150..200).all(|i| map.get(&i) == Some(&(i * 2))));
+        assert!((0..150).all(|i| !map.contains_key(&i)));
+        map.check_invariants();
+    });
+    // Only the new buckets are allocated.
+    assert_eq!(allocations, 1);
+}
+
+#[test]
+fn test_boxed_iters() {
+    struct Summary<'a> {
+        values: Box<dyn Iterator<Item = &'a i32> + 'a>,
+    }
+
+    let map: HashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+    let summary = Summary { values: map.boxed_values() };
+    assert_eq!(summary.values.sum::<i32>(), 450);
+    assert_eq!(map.boxed_keys().sum::<i32>(), 45);
+    assert_eq!(map.boxed_iter().filter(|entry| *entry.0 * 10 == *entry.1).count(), 10);
+}
+
+#[test]
+fn test_retain_returning() {
+    let mut map: HashMap<_, _> = (0..100).map(|i| (i, i)).collect();
+    let capacity = map.capacity();
+    let mut evicted = map.retain_returning(|_, val| *val % 2 == 0);
+    evicted.sort_unstable();
+    assert!(evicted.iter().copied().eq((1..100).step_by(2).map(|i| (i, i))));
+    assert_eq!(map.len(), 50);
+    assert_eq!(map.capacity(), capacity);
+    assert!((0..100).all(|i| map.contains_key(&i) == (i % 2 == 0)));
+    map.check_invariants();
+}
+
+#[test]
+fn test_load_stats() {
+    let stats = HashMap::<i32, i32>::new().load_stats();
+    assert_eq!(stats, LoadStats { min: 0, max: 0, mean: 0.0, empty: 0 });
+
+    let map: HashMap<_, _> = (0..100).map(|i| (i, i)).collect();
+    let stats = map.load_stats();
+    assert!(stats.min <= stats.max && stats.max < HashMap::<i32, i32>::MAX_BUCKET_LEN);
+    assert!(stats.empty < map.capacity());
+    #[allow(clippy::cast_precision_loss)]
+    let mean = 100.0 / map.capacity() as f64;
+    assert!((stats.mean - mean).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_validate_hash_eq_samples() {
+    struct Key<'a> {
+        id: u32,
+        comparisons: &'a core::cell::Cell<usize>,
+    }
+    impl PartialEq for Key<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.comparisons.set(self.comparisons.get() + 1);
+            self.id == other.id
+        }
+    }
+    impl Eq for Key<'_> {}
+    impl Hash for Key<'_> {
+        fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+            self.id.hash(state);
+        }
+    }
+
+    let comparisons = core::cell::Cell::new(0);
+    let map: HashMap<_, _> =
+        (0..127).map(|id| (Key { id, comparisons: &comparisons }, ())).collect();
+    comparisons.set(0);
+    map.validate_hash_eq();
+    // Each sampled key is compared against at most every other key.
+    assert!(comparisons.get() <= 64 * 127, "made {} comparisons", comparisons.get());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "equal keys must have equal hashes"]
+fn test_validate_hash_eq() {
+    // Compares by `id` only, but also hashes `version`.
+    struct Key {
+        id: u32,
+        version: u32,
+    }
+    impl PartialEq for Key {
+        fn eq(&self, other: &Self) -> bool {
+            self.id == other.id
+        }
+    }
+    impl Eq for Key {}
+    impl Hash for Key {
+        fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+            (self.id, self.version).hash(state);
+        }
+    }
+
+    let mut map = HashMap::new();
+    for id in 0..10 {
+        map.insert(Key { id, version: 0 }, ());
+    }
+    map.validate_hash_eq();
+    for version in 1..10 {
+        map.insert(Key { id: 0, version }, ());
+    }
+    map.validate_hash_eq();
+}
+
+#[test]
+fn test_with_capacity_and_load() {
+    let capacity = |entries, max_load| {
+        HashMap::<i32, i32>::with_capacity_and_load(entries, max_load).capacity()
+    };
+    assert_eq!(capacity(100, 1.0), 128);
+    assert_eq!(capacity(100, 0.25), 512);
+    assert_eq!(capacity(96, 0.75), 128);
+    assert_eq!(capacity(97, 0.75), 256);
+    assert_eq!(capacity(1, 1.0), HashMap::<(), ()>::START_CAPACITY);
+    assert_eq!(capacity(0, 0.5), 0);
+
+    let mut map = HashMap::with_capacity_and_load(1000, 0.5);
+    assert_eq!(map.capacity(), 2048);
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    assert_eq!(map.capacity(), 2048);
+    map.check_invariants();
+}
+
+#[test]
+#[should_panic = "max_load was 0, but must be in (0.0, 1.0]"]
+fn test_with_capacity_and_load_zero() {
+    let _ = HashMap::<i32, i32>::with_capacity_and_load(10, 0.0);
+}
+
+#[test]
+fn test_get_or_compute() {
+    use core::cell::RefCell;
+
+    fn fib(memo: &mut HashMap<u64, u64>, computed: &RefCell<Vec<u64>>, n: u64) -> u64 {
+        memo.get_or_compute(n, |memo, &n| {
+            computed.borrow_mut().push(n);
+            if n < 2 {
+                n
+            } else {
+                fib(memo, computed, n - 1) + fib(memo, computed, n - 2)
+            }
+        })
+    }
+
+    let mut memo = HashMap::new();
+    let computed = RefCell::new(Vec::new());
+    assert_eq!(fib(&mut memo, &computed, 50), 12_586_269_025);
+    assert_eq!(fib(&mut memo, &computed, 40), 102_334_155);
+    let mut computed = computed.into_inner();
+    computed.sort_unstable();
+    assert!(computed.iter().copied().eq(0..=50));
+
+    let mut map = HashMap::new();
+    let val = map.get_or_compute("lorem", |map, &key| {
+        map.insert(key, 1);
+        2
+    });
+    assert_eq!(val, 1);
+    assert_eq!(map.get("lorem"), Some(&1));
+    map.check_invariants();
+}
@@ -6,27 +6,34 @@ use core::{
     hash::{BuildHasher, Hash},
 };
 
-use crate::{hasher::PlainBuildHasher, HashMap};
+use crate::{hasher::PlainBuildHasher, hashmap::LoadStats, HashMap};
 
 pub struct HashSet<T, S = PlainBuildHasher> {
     inner: HashMap<T, (), S>,
 }
 
 impl<T> HashSet<T> {
+    /// Doesn't allocate, so it can be used to initialize constants.
     #[must_use]
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Self { inner: HashMap::new() }
     }
 }
 
 impl<T, S> HashSet<T, S> {
+    /// Doesn't allocate, so it can be used to initialize constants.
     #[must_use]
-    pub fn len(&self) -> usize {
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self { inner: HashMap::with_hasher(hasher) }
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
         self.inner.len()
     }
 
     #[must_use]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
@@ -40,10 +47,62 @@ where
     T: Hash + Eq,
     S: BuildHasher,
 {
+    /// Builds the union of `a` and `b`, reserving room for both up front.
+    pub fn from_iters<I1, I2>(a: I1, b: I2) -> Self
+    where
+        I1: IntoIterator<Item = T>,
+        I2: IntoIterator<Item = T>,
+        S: Default,
+    {
+        let (a, b) = (a.into_iter(), b.into_iter());
+        let mut set = Self::default();
+        set.reserve(a.size_hint().0.saturating_add(b.size_hint().0));
+        for val in a.chain(b) {
+            set.insert(val);
+        }
+        set
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+    }
+
     pub fn insert(&mut self, val: T) -> Option<T> {
         self.inner.insert(val, ()).map(|entry| entry.0)
     }
 
+    /// Returns a reference to the stored element equal to `val`, storing `val`
+    /// first if there is none, which makes the set usable as an interner.
+    pub fn get_or_insert(&mut self, val: T) -> &T {
+        if !self.contains(&val) {
+            return self.inner.insert_absent(val, ()).0;
+        }
+        let Some(stored) = self.get(&val) else { unreachable!() };
+        stored
+    }
+
+    /// Like `get_or_insert`, but only builds the element to store, with
+    /// `make`, if no equal one is stored yet. `make(val)` should equal `val`.
+    pub fn get_or_insert_with<Q>(&mut self, val: &Q, make: impl FnOnce(&Q) -> T) -> &T
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.inner.contains_key(val) {
+            return self.inner.insert_absent(make(val), ()).0;
+        }
+        let Some(stored) = self.get(val) else { unreachable!() };
+        stored
+    }
+
+    pub fn get<Q>(&self, val: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.inner.get_key_value(val).map(|entry| entry.0)
+    }
+
     pub fn contains<Q>(&self, val: &Q) -> bool
     where
         T: Borrow<Q>,
@@ -59,6 +118,69 @@ where
     {
         self.inner.remove_entry(val).map(|entry| entry.0)
     }
+
+    /// Keeps only the elements that are also in `other`, like Python's `&=`.
+    pub fn retain_intersection<S2: BuildHasher>(&mut self, other: &HashSet<T, S2>) {
+        self.inner.extract_if(|val, ()| !other.contains(val)).for_each(drop);
+    }
+
+    /// Removes every element that is in `other`, like Python's `-=`. Iterates
+    /// whichever of the two sets is smaller.
+    pub fn remove_all<S2: BuildHasher>(&mut self, other: &HashSet<T, S2>) {
+        if other.len() < self.len() {
+            for val in other {
+                self.remove(val);
+            }
+        } else {
+            self.inner.extract_if(|val, ()| other.contains(val)).for_each(drop);
+        }
+    }
+
+    /// Bucket chain lengths of the underlying map. See `HashMap::load_stats`.
+    #[must_use]
+    pub fn load_stats(&self) -> LoadStats {
+        self.inner.load_stats()
+    }
+
+    /// Checks a sample of elements for signs of a `Hash` impl that disagrees
+    /// with `Eq`. Does nothing in release builds. See
+    /// `HashMap::validate_hash_eq`.
+    ///
+    /// # Panics
+    /// Panics in debug builds with a diagnostic if an inconsistency is found.
+    pub fn validate_hash_eq(&self) {
+        self.inner.validate_hash_eq();
+    }
+
+    /// Iterates the smaller of the two sets and probes the larger one.
+    #[must_use]
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        let (small, large) = if self.len() <= other.len() { (self, other) } else { (other, self) };
+        small.iter().all(|val| !large.contains(val))
+    }
+
+    /// A larger set can never be a subset, so only `self` is ever iterated
+    /// when it's the smaller one.
+    #[must_use]
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|val| other.contains(val))
+    }
+
+    #[must_use]
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl<T, S> HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    pub(crate) fn check_invariants(&self) {
+        self.inner.check_invariants();
+    }
 }
 
 impl<T, S> FromIterator<T> for HashSet<T, S>
@@ -119,3 +241,118 @@ fn test_basics() {
         assert!(!set.contains(&i), "{i}");
     }
 }
+
+#[cfg(test)]
+#[derive(Clone)]
+struct CountingBuildHasher<'a>(&'a core::cell::Cell<usize>);
+
+#[cfg(test)]
+impl BuildHasher for CountingBuildHasher<'_> {
+    type Hasher = <PlainBuildHasher as BuildHasher>::Hasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        self.0.set(self.0.get() + 1);
+        PlainBuildHasher::default().build_hasher()
+    }
+}
+
+#[test]
+fn test_relations_probe_smaller() {
+    let probes = core::cell::Cell::new(0);
+    let mut small = HashSet::with_hasher(CountingBuildHasher(&probes));
+    let mut large = HashSet::with_hasher(CountingBuildHasher(&probes));
+    for i in 1..=5 {
+        small.insert(i);
+    }
+    for i in 10..10_010 {
+        large.insert(i);
+    }
+
+    let count_probes = |f: &dyn Fn() -> bool| {
+        probes.set(0);
+        f();
+        probes.get()
+    };
+    assert!(small.is_disjoint(&large));
+    assert_eq!(count_probes(&|| small.is_disjoint(&large)), 5);
+    assert_eq!(count_probes(&|| large.is_disjoint(&small)), 5);
+
+    assert!(!small.is_subset(&large));
+    assert!(!large.is_subset(&small));
+    assert!(count_probes(&|| small.is_subset(&large)) <= 5);
+    assert_eq!(count_probes(&|| large.is_subset(&small)), 0);
+    assert!(count_probes(&|| large.is_superset(&small)) <= 5);
+
+    let subset = (10..15).collect::<HashSet<_>>();
+    let superset = (10..10_010).collect::<HashSet<_>>();
+    assert!(subset.is_subset(&superset));
+    assert!(superset.is_superset(&subset));
+    assert!(!superset.is_subset(&subset));
+}
+
+#[test]
+fn test_interning() {
+    use alloc::string::String;
+
+    let mut set = HashSet::<String>::new();
+    let hello: *const String = set.get_or_insert_with("hello", |val| String::from(val));
+    let world: *const String = set.get_or_insert(String::from("world"));
+    assert_ne!(hello, world);
+
+    // Growing the buckets doesn't move the stored elements.
+    for i in 0..100 {
+        set.get_or_insert(alloc::format!("{i}"));
+    }
+    assert!(core::ptr::eq(set.get_or_insert_with("hello", |_| unreachable!()), hello));
+    assert!(core::ptr::eq(set.get_or_insert(String::from("world")), world));
+    assert_eq!(set.len(), 102);
+    assert_eq!(set.get("hello"), Some(&String::from("hello")));
+    assert_eq!(set.get("missing"), None);
+}
+
+#[test]
+fn test_from_iters() {
+    let set = HashSet::<i32>::from_iters(0..500, 250..750);
+    assert_eq!(set.len(), 750);
+    assert!(set.inner.capacity() >= 750);
+    assert!((0..750).all(|val| set.contains(&val)));
+    set.check_invariants();
+}
+
+#[test]
+fn test_validate_hash_eq() {
+    // Compiles in every build profile, and passes for a well-behaved `Hash`.
+    let set: HashSet<i32> = (0..100).collect();
+    set.validate_hash_eq();
+}
+
+#[test]
+fn test_load_stats() {
+    let set: HashSet<i32> = (0..1000).collect();
+    let stats = set.load_stats();
+    assert!(stats.max <= 4, "max chain length was {}", stats.max);
+    assert!(stats.min <= stats.max);
+    assert!(stats.empty < set.inner.capacity());
+}
+
+#[test]
+fn test_in_place_algebra() {
+    let overlapping: HashSet<i32> = (75..125).collect();
+
+    let mut set: HashSet<i32> = (0..100).collect();
+    set.retain_intersection(&overlapping);
+    assert_eq!(set.len(), 25);
+    assert!((75..100).all(|val| set.contains(&val)));
+    set.check_invariants();
+
+    let mut set: HashSet<i32> = (0..100).collect();
+    set.remove_all(&overlapping);
+    assert_eq!(set.len(), 75);
+    assert!((0..75).all(|val| set.contains(&val)));
+    set.check_invariants();
+
+    let mut small: HashSet<i32> = (70..80).collect();
+    small.remove_all(&overlapping);
+    assert_eq!(small.len(), 5);
+    assert!((70..75).all(|val| small.contains(&val)));
+}
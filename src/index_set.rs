@@ -0,0 +1,308 @@
+#![forbid(unsafe_code)]
+
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{hasher::PlainBuildHasher, HashMap, Vec};
+
+type Idx = usize;
+const NIL: usize = Idx::MAX;
+
+/// A set that iterates in insertion order.
+///
+/// Elements live contiguously in a `Vec`. Elements sharing a hash are chained
+/// through their indices, with `heads` mapping each hash to its first element.
+pub struct IndexSet<T, S = PlainBuildHasher> {
+    entries: Vec<Entry<T>>,
+    heads: HashMap<u64, Idx>,
+    hasher: S,
+}
+
+struct Entry<T> {
+    val: T,
+    hash: u64,
+    next: Idx,
+}
+
+impl<T> IndexSet<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_hasher(PlainBuildHasher::new())
+    }
+}
+
+impl<T, S> IndexSet<T, S> {
+    #[must_use]
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self { entries: Vec::new(), heads: HashMap::new(), hasher }
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.entries.iter().map(|entry| &entry.val)
+    }
+
+    #[must_use]
+    pub fn get_index(&self, index: usize) -> Option<&T> {
+        self.entries.get(index).map(|entry| &entry.val)
+    }
+
+    /// Makes the link in `hash`'s chain that points at `from` point at `to`.
+    fn relink(&mut self, hash: u64, from: Idx, to: Idx) {
+        let head = self.heads.get_mut(&hash).unwrap();
+        if *head == from {
+            if to == NIL {
+                self.heads.remove(&hash);
+            } else {
+                *head = to;
+            }
+            return;
+        }
+        let mut current = *head;
+        while self.entries[current].next != from {
+            current = self.entries[current].next;
+        }
+        self.entries[current].next = to;
+    }
+}
+
+impl<T, S> IndexSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Appends `val`, or replaces an equal element in place, keeping its
+    /// position.
+    pub fn insert(&mut self, val: T) -> Option<T> {
+        if let Some(index) = self.get_index_of(&val) {
+            return Some(core::mem::replace(&mut self.entries[index].val, val));
+        }
+        let hash = self.hasher.hash_one(&val);
+        let index = self.entries.len();
+        let next = self.heads.insert(hash, index).map_or(NIL, |entry| entry.1);
+        self.entries.push(Entry { val, hash, next });
+        None
+    }
+
+    pub fn get_index_of<Q>(&self, val: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut current = *self.heads.get(&self.hasher.hash_one(val))?;
+        while current != NIL {
+            let entry = &self.entries[current];
+            if entry.val.borrow() == val {
+                return Some(current);
+            }
+            current = entry.next;
+        }
+        None
+    }
+
+    pub fn contains<Q>(&self, val: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_index_of(val).is_some()
+    }
+
+    /// Removes `val` in O(1) by moving the last element into its place, which
+    /// changes the order. Use `shift_remove` to keep the order.
+    pub fn remove<Q>(&mut self, val: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.unlink(val)?;
+        let entry = self.entries.swap_remove(index);
+        if let Some(moved) = self.entries.get(index).map(|moved| moved.hash) {
+            self.relink(moved, self.entries.len(), index);
+        }
+        Some(entry.val)
+    }
+
+    /// Removes `val` while keeping the order of the remaining elements, which
+    /// takes O(len).
+    pub fn shift_remove<Q>(&mut self, val: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.unlink(val)?;
+        let entry = self.entries.remove(index);
+        let shift = |current: &mut Idx| {
+            if *current != NIL && *current > index {
+                *current -= 1;
+            }
+        };
+        self.entries.iter_mut().for_each(|entry| shift(&mut entry.next));
+        self.heads.values_mut().for_each(shift);
+        Some(entry.val)
+    }
+
+    /// Elements of `self` followed by the elements only in `other`.
+    pub fn union<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> {
+        self.iter().chain(other.difference(self))
+    }
+
+    /// Elements of `self` that are also in `other`, in `self`'s order.
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> {
+        self.iter().filter(|val| other.contains(*val))
+    }
+
+    /// Elements of `self` that aren't in `other`, in `self`'s order.
+    pub fn difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> {
+        self.iter().filter(|val| !other.contains(*val))
+    }
+
+    /// Elements only in `self`, followed by the elements only in `other`.
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a T> {
+        self.difference(other).chain(other.difference(self))
+    }
+
+    /// Finds `val` and takes it out of its hash chain, returning its index.
+    fn unlink<Q>(&mut self, val: &Q) -> Option<Idx>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.get_index_of(val)?;
+        let Entry { hash, next, .. } = self.entries[index];
+        self.relink(hash, index, next);
+        Some(index)
+    }
+}
+
+impl<T, S> Extend<T> for IndexSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.entries.reserve(iter.size_hint().0);
+        for val in iter {
+            self.insert(val);
+        }
+    }
+}
+
+impl<T, S> FromIterator<T> for IndexSet<T, S>
+where
+    T: Hash + Eq,
+    S: Default + BuildHasher,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, S> IntoIterator for IndexSet<T, S> {
+    type Item = T;
+
+    type IntoIter = impl DoubleEndedIterator<Item = T> + ExactSizeIterator;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter().map(|entry| entry.val)
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a IndexSet<T, S> {
+    type Item = &'a T;
+
+    type IntoIter = impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|entry| &entry.val)
+    }
+}
+
+impl<T, S> Default for IndexSet<T, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<T, S> fmt::Debug for IndexSet<T, S>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self).finish()
+    }
+}
+
+#[test]
+fn test_insertion_order() {
+    let mut set: IndexSet<_> = [5, 3, 8, 1, 9].into_iter().collect();
+    assert!(set.iter().eq(&[5, 3, 8, 1, 9]));
+    assert_eq!(set.insert(3), Some(3));
+    assert!(set.iter().eq(&[5, 3, 8, 1, 9]));
+    assert_eq!(set.get_index_of(&8), Some(2));
+
+    assert_eq!(set.shift_remove(&8), Some(8));
+    assert!(set.iter().eq(&[5, 3, 1, 9]));
+    assert!(!set.contains(&8));
+    assert!([5, 3, 1, 9].iter().all(|val| set.contains(val)));
+
+    assert_eq!(set.remove(&3), Some(3));
+    assert!(set.iter().eq(&[5, 9, 1]));
+    assert_eq!(set.remove(&3), None);
+    assert_eq!(set.get_index_of(&9), Some(1));
+
+    set.insert(3);
+    assert!(set.iter().eq(&[5, 9, 1, 3]));
+}
+
+#[test]
+fn test_colliding_hashes() {
+    #[derive(Default)]
+    struct ConstHasher;
+    impl core::hash::Hasher for ConstHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, _: &[u8]) {}
+    }
+
+    let mut set: IndexSet<_, core::hash::BuildHasherDefault<ConstHasher>> = (0..10).collect();
+    assert!(set.iter().copied().eq(0..10));
+    assert_eq!(set.remove(&0), Some(0));
+    assert_eq!(set.shift_remove(&5), Some(5));
+    assert_eq!(set.remove(&7), Some(7));
+    assert!(set.iter().eq(&[9, 1, 2, 3, 4, 6, 8]));
+    assert!(set.iter().enumerate().all(|(index, val)| set.get_index_of(val) == Some(index)));
+    assert!(!set.contains(&0) && !set.contains(&5) && !set.contains(&7));
+}
+
+#[test]
+fn test_set_algebra() {
+    let a: IndexSet<_> = [1, 2, 3, 4].into_iter().collect();
+    let b: IndexSet<_> = [6, 4, 2, 5].into_iter().collect();
+
+    assert!(a.union(&b).eq(&[1, 2, 3, 4, 6, 5]));
+    assert!(a.intersection(&b).eq(&[2, 4]));
+    assert!(b.intersection(&a).eq(&[4, 2]));
+    assert!(a.difference(&b).eq(&[1, 3]));
+    assert!(a.symmetric_difference(&b).eq(&[1, 3, 6, 5]));
+}
@@ -0,0 +1,91 @@
+#![forbid(unsafe_code)]
+
+pub trait IteratorExt: Iterator + Sized {
+    /// Like `zip`, but panics if the two iterators have different lengths
+    /// instead of silently stopping at the shorter one.
+    ///
+    /// # Panics
+    /// Panics right away if `self`'s size hint is exact and differs from
+    /// `other`'s length, and otherwise once one side runs out before the other.
+    fn zip_exact<I>(self, other: I) -> ZipExact<Self, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let other = other.into_iter();
+        if let (lower, Some(upper)) = self.size_hint() {
+            assert!(
+                lower != upper || lower == other.len(),
+                "zip_exact called on iterators of different lengths"
+            );
+        }
+        ZipExact { a: self, b: other }
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+pub struct ZipExact<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Iterator for ZipExact<A, B>
+where
+    A: Iterator,
+    B: ExactSizeIterator,
+{
+    type Item = (A::Item, B::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.a.next(), self.b.next()) {
+            (Some(a), Some(b)) => Some((a, b)),
+            (None, None) => None,
+            _ => panic!("zip_exact called on iterators of different lengths"),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.b.len(), Some(self.b.len()))
+    }
+}
+
+impl<A, B> ExactSizeIterator for ZipExact<A, B>
+where
+    A: Iterator,
+    B: ExactSizeIterator,
+{
+}
+
+#[test]
+fn test_zip_exact() {
+    use crate::{linked_list::LinkedList, HashMap};
+
+    let a: LinkedList<_> = (0..5).collect();
+    let b: LinkedList<_> = (5..10).collect();
+    assert!(a.iter().zip_exact(&b).map(|(a, b)| a + b).eq([5, 7, 9, 11, 13]));
+    assert_eq!(a.iter().zip_exact(&b).len(), 5);
+
+    let map: HashMap<_, _> = (0..5).map(|i| (i, i)).collect();
+    assert_eq!(map.iter().zip_exact(&a).count(), 5);
+}
+
+#[test]
+#[should_panic = "different lengths"]
+fn test_zip_exact_mismatch() {
+    use crate::linked_list::LinkedList;
+
+    let a: LinkedList<_> = (0..5).collect();
+    let b: LinkedList<_> = (0..4).collect();
+    let _ = a.iter().zip_exact(&b);
+}
+
+#[test]
+#[should_panic = "different lengths"]
+fn test_zip_exact_mismatch_lazy() {
+    use crate::{linked_list::LinkedList, HashMap};
+
+    let map: HashMap<_, _> = (0..5).map(|i| (i, i)).collect();
+    let list: LinkedList<_> = (0..4).collect();
+    map.iter().zip_exact(&list).for_each(drop);
+}
@@ -3,12 +3,26 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(test)]
+extern crate std;
 
+pub mod array_vec;
+pub mod counter;
+pub mod cow_map;
 pub mod hasher;
 pub mod hashmap;
 pub mod hashset;
+pub mod index_set;
+pub mod iter;
 pub mod linked_list;
+#[cfg(test)]
+mod panic_safety;
 pub(crate) mod raw_vec;
+pub mod ring_buffer;
+#[cfg(test)]
+mod test_alloc;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod vec;
 
 pub use hashmap::HashMap;
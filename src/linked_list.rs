@@ -43,13 +43,83 @@ impl<T> LinkedList<T> {
         self.head = ptr;
     }
 
+    /// Pushes every item to the front, one after another.
+    ///
+    /// This behaves like repeated `push_front`, so the items end up in reverse
+    /// iteration order: extending `[3]` with `[1, 2]` gives `[2, 1, 3]`.
+    pub fn extend_front(&mut self, iter: impl IntoIterator<Item = T>) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for val in iter {
+            self.push_front(val);
+        }
+    }
+
+    /// Moves all of `other`'s elements to the front of `self`, leaving `other`
+    /// empty.
+    ///
+    /// Unlike `extend_front`, the elements keep their order, so prepending
+    /// `[1, 2]` to `[3]` gives `[1, 2, 3]`.
+    pub fn prepend(&mut self, other: &mut Self) {
+        self.reserve(other.len());
+        while let Some(val) = other.pop_back() {
+            self.push_front(val);
+        }
+    }
+
+    /// The logical index of the first element matching `pred`, counting from
+    /// the head rather than by position in the arena.
+    pub fn position(&self, pred: impl FnMut(&T) -> bool) -> Option<usize> {
+        self.iter().position(pred)
+    }
+
+    /// The first element from the head matching `pred`.
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<&T> {
+        self.iter().find(|val| pred(val))
+    }
+
+    /// Splits the list before the first element matching `pred`, returning the
+    /// rest of the list starting at that element. Returns `None`, leaving
+    /// `self` untouched, if nothing matches.
+    pub fn split_when(&mut self, pred: impl FnMut(&T) -> bool) -> Option<Self> {
+        let index = self.iter().position(pred)?;
+        if index == 0 {
+            return Some(mem::take(self));
+        }
+        let mut tail = Self::new();
+        tail.reserve(self.len() - index);
+        while self.len() > index {
+            let Some(val) = self.pop_back() else { unreachable!() };
+            tail.push_front(val);
+        }
+        Some(tail)
+    }
+
+    /// Keeps the first `len` elements, dropping the rest from the back. Does
+    /// nothing if `len >= self.len()`.
+    pub fn truncate_back(&mut self, len: usize) {
+        while self.len() > len {
+            self.pop_back();
+        }
+    }
+
+    /// Keeps the last `len` elements, dropping the rest from the front. Does
+    /// nothing if `len >= self.len()`.
+    pub fn truncate_front(&mut self, len: usize) {
+        while self.len() > len {
+            self.pop_front();
+        }
+    }
+
     pub fn pop_back(&mut self) -> Option<T> {
         if self.is_empty() {
             return None;
         }
         let node = self.remove_node(self.tail);
         self.tail = node.prev;
-        if self.tail != NIL {
+        if self.tail == NIL {
+            self.head = NIL;
+        } else {
             self.buf[self.tail].next = NIL;
         }
         Some(node.val)
@@ -61,7 +131,9 @@ impl<T> LinkedList<T> {
         }
         let node = self.remove_node(self.head);
         self.head = node.next;
-        if self.head != NIL {
+        if self.head == NIL {
+            self.tail = NIL;
+        } else {
             self.buf[self.head].prev = NIL;
         }
         Some(node.val)
@@ -77,6 +149,11 @@ impl<T> LinkedList<T> {
         self.len() == 0
     }
 
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.buf.cap()
+    }
+
     #[must_use]
     pub fn iter(&self) -> Iter<'_, T> {
         self.into_iter()
@@ -112,6 +189,20 @@ impl<T> LinkedList<T> {
         node
     }
 
+    /// Links `val` in right before `next`, or at the back if `next` is `NIL`.
+    fn insert_before(&mut self, next: Idx, val: T) {
+        if next == NIL {
+            return self.push_back(val);
+        }
+        let prev = self.buf[next].prev;
+        if prev == NIL {
+            return self.push_front(val);
+        }
+        let ptr = self.push_buf(Node { val, next, prev });
+        self.buf[prev].next = ptr;
+        self.buf[next].prev = ptr;
+    }
+
     fn push_buf(&mut self, node: Node<T>) -> Idx {
         let ptr = self.buf.len();
         self.buf.push(node);
@@ -119,6 +210,21 @@ impl<T> LinkedList<T> {
     }
 }
 
+impl<T> LinkedList<T>
+where
+    T: Ord,
+{
+    /// Inserts `val` before the first element greater than it, so a sorted
+    /// list stays sorted. Equal elements keep their insertion order.
+    pub fn insert_sorted(&mut self, val: T) {
+        let mut current = self.head;
+        while current != NIL && self.buf[current].val <= val {
+            current = self.buf[current].next;
+        }
+        self.insert_before(current, val);
+    }
+}
+
 impl<T> Default for LinkedList<T> {
     fn default() -> Self {
         Self::new()
@@ -138,11 +244,11 @@ impl<T> Iterator for IntoIter<T> {
     }
 
     fn count(self) -> usize {
-        self.len()
+        self.list.len()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len(), Some(self.len()))
+        (self.list.len(), Some(self.list.len()))
     }
 }
 
@@ -154,6 +260,15 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 
 impl<T> ExactSizeIterator for IntoIter<T> {}
 
+impl<T> IntoIter<T> {
+    /// Turns the elements not yet yielded back into a list, keeping the
+    /// original allocation for reuse.
+    #[must_use]
+    pub fn into_remaining_list(self) -> LinkedList<T> {
+        self.list
+    }
+}
+
 impl<T> IntoIterator for LinkedList<T> {
     type IntoIter = IntoIter<T>;
     type Item = T;
@@ -181,11 +296,11 @@ impl<'a, T> Iterator for Iter<'a, T> {
     }
 
     fn count(self) -> usize {
-        self.len()
+        self.len
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len(), Some(self.len()))
+        (self.len, Some(self.len))
     }
 }
 
@@ -334,6 +449,27 @@ fn test_basics() {
     assert_eq!(list.pop_back(), Some(3));
 }
 
+#[test]
+fn test_pop_to_empty() {
+    let mut list = LinkedList::new();
+    list.push_back(1);
+    assert_eq!(list.pop_back(), Some(1));
+    assert!(list.is_empty());
+    list.push_front(2);
+    list.push_back(3);
+    assert!(list.iter().eq(&[2, 3]));
+    assert!(list.iter().rev().eq(&[3, 2]));
+
+    let mut list = LinkedList::new();
+    list.push_front(1);
+    assert_eq!(list.pop_front(), Some(1));
+    assert!(list.is_empty());
+    list.push_back(2);
+    list.push_front(3);
+    assert!(list.iter().eq(&[3, 2]));
+    assert!(list.iter().rev().eq(&[2, 3]));
+}
+
 #[allow(clippy::cognitive_complexity)]
 #[test]
 fn test_iter() {
@@ -376,3 +512,111 @@ fn test_iter() {
     assert_eq!(iter.next_back(), None);
     assert_eq!(iter.next(), None);
 }
+
+#[test]
+fn test_extend_front() {
+    let mut list: LinkedList<_> = [3, 4].into_iter().collect();
+    list.extend_front([2, 1]);
+    assert!(list.iter().eq(&[1, 2, 3, 4]));
+    assert!(list.iter().rev().eq(&[4, 3, 2, 1]));
+
+    let mut other: LinkedList<_> = [-1, 0].into_iter().collect();
+    list.prepend(&mut other);
+    assert!(other.is_empty());
+    assert!(list.iter().eq(&[-1, 0, 1, 2, 3, 4]));
+    assert!(list.iter().rev().eq(&[4, 3, 2, 1, 0, -1]));
+
+    let mut empty = LinkedList::new();
+    empty.prepend(&mut list);
+    assert!(list.is_empty());
+    assert!(empty.iter().eq(&[-1, 0, 1, 2, 3, 4]));
+
+    list.extend_front([2, 1]);
+    assert!(list.iter().eq(&[1, 2]));
+    assert!(list.iter().rev().eq(&[2, 1]));
+}
+
+#[test]
+fn test_insert_sorted() {
+    let mut list = LinkedList::new();
+    for i in [3, 1, 2] {
+        list.insert_sorted(i);
+    }
+    assert!(list.iter().eq(&[1, 2, 3]));
+
+    for i in [0, 5, 2, 4] {
+        list.insert_sorted(i);
+    }
+    assert!(list.iter().eq(&[0, 1, 2, 2, 3, 4, 5]));
+    assert!(list.iter().rev().eq(&[5, 4, 3, 2, 2, 1, 0]));
+}
+
+#[test]
+fn test_into_remaining_list() {
+    let list: LinkedList<_> = (0..10).collect();
+    let capacity = list.capacity();
+    let mut iter = list.into_iter();
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next(), Some(1));
+
+    let mut list = iter.into_remaining_list();
+    assert!(list.iter().copied().eq(2..10));
+    assert_eq!(list.capacity(), capacity);
+    list.push_front(1);
+    list.push_back(10);
+    assert!(list.iter().copied().eq(1..11));
+    assert_eq!(list.capacity(), capacity);
+}
+
+#[test]
+fn test_split_when() {
+    let mut list: LinkedList<_> = (1..=4).collect();
+    let tail = list.split_when(|&val| val > 2).unwrap();
+    assert!(list.iter().copied().eq([1, 2]));
+    assert!(tail.iter().copied().eq([3, 4]));
+
+    assert!(list.split_when(|&val| val > 2).is_none());
+    assert!(list.iter().copied().eq([1, 2]));
+
+    let tail = list.split_when(|_| true).unwrap();
+    assert!(list.is_empty());
+    assert!(tail.iter().copied().eq([1, 2]));
+    list.push_back(5);
+    assert!(list.iter().copied().eq([5]));
+}
+
+#[test]
+fn test_truncate() {
+    let mut list: LinkedList<_> = (0..10).collect();
+    list.truncate_back(3);
+    assert!(list.iter().copied().eq(0..3));
+    assert!(list.iter().rev().copied().eq((0..3).rev()));
+
+    let mut list: LinkedList<_> = (0..10).collect();
+    list.truncate_front(3);
+    assert!(list.iter().copied().eq(7..10));
+    assert!(list.iter().rev().copied().eq((7..10).rev()));
+    list.push_front(6);
+    list.push_back(10);
+    assert!(list.iter().copied().eq(6..11));
+
+    list.truncate_back(10);
+    assert_eq!(list.len(), 5);
+    list.truncate_front(0);
+    assert!(list.is_empty());
+    list.push_back(1);
+    assert!(list.iter().copied().eq([1]));
+}
+
+#[test]
+fn test_position_find() {
+    let mut list = LinkedList::new();
+    // Pushing to the front stores the elements in the reverse of their
+    // logical order.
+    list.extend_front([4, 3, 2, 1, 0]);
+    assert_eq!(list.position(|&val| val == 3), Some(3));
+    assert_eq!(list.find(|&val| val > 1), Some(&2));
+    assert_eq!(list.position(|&val| val == 9), None);
+    assert_eq!(list.find(|&val| val > 9), None);
+    assert_eq!(LinkedList::<i32>::new().position(|_| true), None);
+}
@@ -0,0 +1,209 @@
+//! Checks that mutating methods leave their collection usable, with every
+//! element dropped exactly once, when user code panics partway through.
+#![forbid(unsafe_code)]
+
+use core::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{array_vec::ArrayVec, HashMap, Vec};
+
+/// Logs its `id` when dropped, panicking afterwards if `panic_on_drop` is set.
+struct Probe<'a> {
+    id: usize,
+    drops: &'a RefCell<Vec<usize>>,
+    panic_on_drop: bool,
+}
+
+impl Drop for Probe<'_> {
+    fn drop(&mut self) {
+        self.drops.borrow_mut().push(self.id);
+        assert!(!self.panic_on_drop, "probe {} panicked on drop", self.id);
+    }
+}
+
+fn probes(drops: &RefCell<Vec<usize>>, len: usize, panic_on_drop: Option<usize>) -> Vec<Probe<'_>> {
+    Vec::from_fn(len, |id| Probe { id, drops, panic_on_drop: panic_on_drop == Some(id) })
+}
+
+fn ids<'a>(probes: impl IntoIterator<Item = &'a Probe<'a>>) -> Vec<usize> {
+    let mut ids: Vec<_> = probes.into_iter().map(|probe| probe.id).collect();
+    ids.sort_unstable();
+    ids
+}
+
+fn assert_panics(f: impl FnOnce()) {
+    assert!(panic::catch_unwind(AssertUnwindSafe(f)).is_err());
+}
+
+/// Asserts that each of the probes `0..len` was dropped exactly once.
+fn assert_dropped_once(drops: &RefCell<Vec<usize>>, len: usize) {
+    let mut drops = drops.borrow_mut();
+    drops.sort_unstable();
+    assert!(drops.iter().copied().eq(0..len), "expected each probe dropped once, got {drops:?}");
+}
+
+#[test]
+fn test_retain_indexed_predicate_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut items = probes(&drops, 8, None);
+    assert_panics(|| {
+        items.retain_indexed(|index, _| {
+            assert!(index != 5);
+            index % 2 == 0
+        });
+    });
+    assert!(ids(&items).iter().copied().eq(0..8));
+    assert!(drops.borrow().is_empty());
+    drop(items);
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_retain_indexed_drop_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut items = probes(&drops, 8, Some(3));
+    assert_panics(|| items.retain_indexed(|index, _| index % 2 == 0));
+    assert!(ids(&items).iter().copied().eq([0, 2, 4, 6]));
+    drop(items);
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_truncate_drop_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut items = probes(&drops, 8, Some(5));
+    assert_panics(|| items.truncate(2));
+    assert!(ids(&items).iter().copied().eq(0..2));
+    drop(items);
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_dedup_returning_key_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut items = probes(&drops, 8, None);
+    assert_panics(|| {
+        let _ = items.dedup_returning(|probe| {
+            assert!(probe.id != 5);
+            probe.id / 2
+        });
+    });
+    // The removed probes are dropped, and the unvisited ones stay in place.
+    assert!(ids(&items).iter().copied().eq([0, 2, 4, 5, 6, 7]));
+    drop(items);
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_coalesce_merge_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut items = probes(&drops, 8, None);
+    assert_panics(|| {
+        items.coalesce(
+            |a, b| a.id / 3 == b.id / 3,
+            |_, probe| assert!(probe.id != 4, "merge panicked"),
+        );
+    });
+    assert!(ids(&items).iter().copied().eq([0, 3, 5, 6, 7]));
+    drop(items);
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_sort_by_cached_key_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut items = probes(&drops, 8, None);
+    assert_panics(|| {
+        items.sort_by_cached_key(|probe| {
+            assert!(probe.id != 6);
+            usize::MAX - probe.id
+        });
+    });
+    assert!(ids(&items).iter().copied().eq(0..8));
+    drop(items);
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_sort_by_u64_key_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut items = probes(&drops, 8, None);
+    assert_panics(|| {
+        items.sort_by_u64_key(|probe| {
+            assert!(probe.id != 5);
+            u64::MAX - probe.id as u64
+        });
+    });
+    assert!(items.iter().map(|probe| probe.id).eq(0..8));
+    assert!(drops.borrow().is_empty());
+    drop(items);
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_into_iter_drop_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut iter = probes(&drops, 8, Some(2)).into_iter();
+    drop(iter.next());
+    drop(iter.next_back());
+    assert_panics(|| drop(iter));
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_into_iter_rfold_panics() {
+    let drops = RefCell::new(Vec::new());
+    let iter = probes(&drops, 8, None).into_iter();
+    assert_panics(|| {
+        iter.rfold((), |(), probe| assert!(probe.id != 4, "rfold panicked"));
+    });
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_into_batches_drop_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut batches = probes(&drops, 8, Some(6)).into_batches(3);
+    drop(batches.next());
+    assert_panics(|| drop(batches));
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_array_vec_into_iter_drop_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut items = ArrayVec::<_, 8>::new();
+    for probe in probes(&drops, 8, Some(4)) {
+        assert!(items.push(probe).is_ok());
+    }
+    let mut iter = items.into_iter();
+    drop(iter.next());
+    assert_panics(|| drop(iter));
+    assert_dropped_once(&drops, 8);
+}
+
+#[test]
+fn test_extract_if_predicate_panics() {
+    let drops = RefCell::new(Vec::new());
+    let mut map: HashMap<_, _> = probes(&drops, 16, None).into_iter().enumerate().collect();
+    assert_panics(|| {
+        let extracted = map.extract_if(|key, _| {
+            assert!(*key != 9);
+            key % 2 == 0
+        });
+        extracted.for_each(drop);
+    });
+    map.check_invariants();
+    assert_eq!(map.len(), 16 - drops.borrow().len());
+    assert!(map.iter().all(|(key, probe)| *key == probe.id));
+    drop(map);
+    assert_dropped_once(&drops, 16);
+}
+
+#[test]
+fn test_hashmap_drop_panics() {
+    let drops = RefCell::new(Vec::new());
+    let map: HashMap<_, _> = probes(&drops, 16, Some(7)).into_iter().enumerate().collect();
+    assert_panics(|| drop(map));
+    assert_dropped_once(&drops, 16);
+}
@@ -6,6 +6,14 @@ use core::{
     ptr::{self, NonNull},
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity would take more than `isize::MAX` bytes.
+    CapacityOverflow,
+    /// The allocator returned null.
+    AllocError { layout: Layout },
+}
+
 pub struct RawVec<T> {
     pub ptr: NonNull<T>,
     pub cap: usize,
@@ -28,30 +36,59 @@ impl<T> RawVec<T> {
     }
 
     pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional).unwrap_or_else(|err| handle_reserve_error(err));
+    }
+
+    /// # Errors
+    /// Fails if the new capacity overflows or the allocator fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let required = self.cap.checked_add(additional).ok_or(TryReserveError::CapacityOverflow)?;
         let new_cap = if self.cap == 0 { Self::START_CAPACITY } else { 2 * self.cap };
-        let new_cap = new_cap.max(self.cap + additional);
-        self.resize(new_cap);
+        self.try_resize(new_cap.max(required))
     }
 
     /// # Panics
     /// Panics if `new_cap * size_of::<T> > isize::MAX`
     pub fn resize(&mut self, new_cap: usize) {
-        let new_layout = Layout::array::<T>(new_cap).unwrap();
+        self.try_resize(new_cap).unwrap_or_else(|err| handle_reserve_error(err));
+    }
 
-        assert!(isize::try_from(new_layout.size()).is_ok(), "Allocation too large");
+    /// # Errors
+    /// Fails if `new_cap * size_of::<T> > isize::MAX` or the allocator fails.
+    pub fn try_resize(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
+        // `Layout::array` also rejects sizes above `isize::MAX`.
+        let layout = |cap| Layout::array::<T>(cap).map_err(|_| TryReserveError::CapacityOverflow);
+        let new_layout = layout(new_cap)?;
+
+        if new_layout.size() == 0 {
+            self.dealloc();
+            self.ptr = NonNull::dangling();
+            self.cap = new_cap;
+            return Ok(());
+        }
 
         let new_ptr = if self.cap == 0 {
             unsafe { alloc::alloc(new_layout) }
         } else {
-            let old_layout = Layout::array::<T>(self.cap).unwrap();
             let old_ptr = self.ptr.as_ptr().cast();
-            unsafe { alloc::realloc(old_ptr, old_layout, new_layout.size()) }
+            unsafe { alloc::realloc(old_ptr, layout(self.cap)?, new_layout.size()) }
         };
 
-        // If allocation fails, `new_ptr` will be null, in which case we abort.
-        self.ptr = NonNull::new(new_ptr)
-            .map_or_else(|| alloc::handle_alloc_error(new_layout), NonNull::cast);
+        // If allocation fails, `new_ptr` will be null and the old allocation is
+        // left untouched.
+        self.ptr =
+            NonNull::new(new_ptr).ok_or(TryReserveError::AllocError { layout: new_layout })?.cast();
         self.cap = new_cap;
+        Ok(())
+    }
+
+    fn dealloc(&mut self) {
+        if mem::size_of::<T>() == 0 || self.cap == 0 {
+            return;
+        }
+        unsafe {
+            alloc::dealloc(self.ptr.as_ptr().cast(), Layout::array::<T>(self.cap).unwrap());
+        }
     }
 
     /// # Safety
@@ -77,12 +114,14 @@ impl<T> RawVec<T> {
 
 impl<T> Drop for RawVec<T> {
     fn drop(&mut self) {
-        if mem::size_of::<T>() == 0 || self.cap == 0 {
-            return;
-        }
-        unsafe {
-            alloc::dealloc(self.ptr.as_ptr().cast(), Layout::array::<T>(self.cap).unwrap());
-        }
+        self.dealloc();
+    }
+}
+
+fn handle_reserve_error(err: TryReserveError) -> ! {
+    match err {
+        TryReserveError::CapacityOverflow => panic!("capacity overflow"),
+        TryReserveError::AllocError { layout } => alloc::handle_alloc_error(layout),
     }
 }
 
@@ -0,0 +1,200 @@
+#![allow(unsafe_code)]
+
+use core::{fmt, ptr, slice};
+
+use crate::raw_vec::RawVec;
+
+/// A fixed-capacity queue that overwrites its oldest element once full, so it
+/// never reallocates after construction.
+pub struct RingBuffer<T> {
+    buf: RawVec<T>,
+    // Index of the oldest element.
+    head: usize,
+    len: usize,
+}
+
+impl<T> RingBuffer<T> {
+    /// # Panics
+    /// Panics if `capacity == 0`.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity != 0, "capacity must be non-zero");
+        let mut buf = RawVec::new();
+        buf.resize(capacity);
+        Self { buf, head: 0, len: 0 }
+    }
+
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.buf.cap
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends `val` as the newest element. If the buffer is full, the oldest
+    /// element is overwritten and returned.
+    pub fn push(&mut self, val: T) -> Option<T> {
+        if self.len < self.capacity() {
+            let index = (self.head + self.len) % self.capacity();
+            unsafe { self.buf.write(index, val) };
+            self.len += 1;
+            return None;
+        }
+        let oldest = unsafe { self.buf.read(self.head) };
+        unsafe { self.buf.write(self.head, val) };
+        self.head = (self.head + 1) % self.capacity();
+        Some(oldest)
+    }
+
+    /// Iterates from the oldest element to the newest.
+    #[must_use]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        let (front, back) = self.as_slices();
+        front.iter().chain(back)
+    }
+
+    /// The elements from oldest to newest, split where they wrap around the
+    /// end of the buffer.
+    #[must_use]
+    pub const fn as_slices(&self) -> (&[T], &[T]) {
+        let front_len = self.front_len();
+        unsafe {
+            (
+                slice::from_raw_parts(self.buf.ptr.as_ptr().add(self.head), front_len),
+                slice::from_raw_parts(self.buf.ptr.as_ptr(), self.len - front_len),
+            )
+        }
+    }
+
+    /// How many elements come before the wrap-around point.
+    const fn front_len(&self) -> usize {
+        let until_end = self.capacity() - self.head;
+        if self.len < until_end {
+            self.len
+        } else {
+            until_end
+        }
+    }
+
+    fn pop_oldest(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let oldest = unsafe { self.buf.read(self.head) };
+        self.head = (self.head + 1) % self.capacity();
+        self.len -= 1;
+        Some(oldest)
+    }
+}
+
+impl<T> Drop for RingBuffer<T> {
+    fn drop(&mut self) {
+        let front_len = self.front_len();
+        let ptr = self.buf.ptr.as_ptr();
+        unsafe {
+            let front = ptr::slice_from_raw_parts_mut(ptr.add(self.head), front_len);
+            let back = ptr::slice_from_raw_parts_mut(ptr, self.len - front_len);
+            self.len = 0;
+            ptr::drop_in_place(front);
+            ptr::drop_in_place(back);
+        }
+    }
+}
+
+impl<T> fmt::Debug for RingBuffer<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> IntoIterator for RingBuffer<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { ring: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+
+    type IntoIter = impl DoubleEndedIterator<Item = &'a T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntoIter<T> {
+    ring: RingBuffer<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ring.pop_oldest()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.ring.len(), Some(self.ring.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+#[test]
+fn test_overwrite() {
+    let mut ring = RingBuffer::with_capacity(4);
+    let allocations = crate::test_alloc::count_allocations(|| {
+        let evicted: [_; 7] = core::array::from_fn(|i| ring.push(i));
+        assert_eq!(evicted, [None, None, None, None, Some(0), Some(1), Some(2)]);
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(ring.len(), 4);
+    assert_eq!(ring.capacity(), 4);
+    assert!(ring.iter().copied().eq(3..7));
+    assert!(ring.iter().rev().copied().eq((3..7).rev()));
+    assert_eq!(ring.as_slices(), (&[3][..], &[4, 5, 6][..]));
+
+    let mut iter = ring.into_iter();
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.next(), Some(3));
+    assert!(iter.eq(4..7));
+}
+
+#[test]
+fn test_drop() {
+    use alloc::rc::Rc;
+
+    let marker = Rc::new(());
+    let mut ring = RingBuffer::with_capacity(3);
+    for _ in 0..5 {
+        ring.push(Rc::clone(&marker));
+    }
+    assert_eq!(Rc::strong_count(&marker), 4);
+    drop(ring);
+    assert_eq!(Rc::strong_count(&marker), 1);
+
+    let mut ring = RingBuffer::with_capacity(3);
+    for _ in 0..5 {
+        ring.push(Rc::clone(&marker));
+    }
+    let mut iter = ring.into_iter();
+    iter.next();
+    assert_eq!(Rc::strong_count(&marker), 3);
+    drop(iter);
+    assert_eq!(Rc::strong_count(&marker), 1);
+}
@@ -0,0 +1,59 @@
+//! A global allocator for tests that counts the allocations made on each
+//! thread, so tests running in parallel don't see each other's allocations.
+#![allow(unsafe_code)]
+
+use core::{cell::Cell, ptr};
+use std::alloc::{GlobalAlloc, Layout, System};
+
+std::thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static FAILING: Cell<bool> = const { Cell::new(false) };
+}
+
+struct CountingAlloc;
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Counts an allocation, returning whether it should fail.
+fn record_allocation() -> bool {
+    // The thread locals may already be gone while the thread is shutting down.
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    FAILING.try_with(Cell::get).unwrap_or(false)
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if record_allocation() {
+            return ptr::null_mut();
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if record_allocation() {
+            return ptr::null_mut();
+        }
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// Returns how many allocations (including reallocations) `f` made on the
+/// current thread.
+pub fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Runs `f` with every allocation on the current thread failing.
+pub fn with_failing_allocations<R>(f: impl FnOnce() -> R) -> R {
+    FAILING.with(|failing| failing.set(true));
+    let ret = f();
+    FAILING.with(|failing| failing.set(false));
+    ret
+}
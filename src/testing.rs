@@ -0,0 +1,43 @@
+//! Helpers for testing custom `Hash`/`Eq` impls and hashers against this
+//! crate's collections. Only available with the `testing` feature.
+#![forbid(unsafe_code)]
+
+use core::hash::{BuildHasher, Hash};
+
+use crate::{HashMap, HashSet};
+
+/// Asserts that `map` is internally consistent: every entry lives in the bucket
+/// its hash dictates, no bucket holds the same key twice, and `len` matches the
+/// number of stored entries.
+///
+/// A `Hash` impl that isn't stable for a key, or that disagrees with `Eq`, will
+/// usually trip this after a few inserts.
+///
+/// # Panics
+/// Panics if any invariant is violated.
+pub fn check_map_invariants<K, V, S>(map: &HashMap<K, V, S>)
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    map.check_invariants();
+}
+
+/// Same as [`check_map_invariants`], for sets.
+///
+/// # Panics
+/// Panics if any invariant is violated.
+pub fn check_set_invariants<T, S>(set: &HashSet<T, S>)
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    set.check_invariants();
+}
+
+#[test]
+fn test_check_set_invariants() {
+    let set = (0..1000).collect::<HashSet<_>>();
+    check_set_invariants(&set);
+    check_map_invariants(&(0..1000).map(|i| (i, ())).collect::<HashMap<_, _>>());
+}
@@ -2,14 +2,17 @@
 
 use alloc::boxed::Box;
 use core::{
+    cmp::Ordering,
     fmt,
+    hash::Hash,
     mem::{self, ManuallyDrop},
-    ops::{Deref, DerefMut},
-    ptr::NonNull,
+    ops::{Bound, Deref, DerefMut, RangeBounds},
+    ptr::{self, NonNull},
 };
 
 #[allow(clippy::module_name_repetitions)]
-pub use crate::raw_vec::RawVec;
+pub use crate::raw_vec::{RawVec, TryReserveError};
+use crate::{HashMap, HashSet};
 
 pub struct Vec<T> {
     buf: RawVec<T>,
@@ -32,6 +35,19 @@ impl<T> Vec<T> {
         ret
     }
 
+    /// Builds a `Vec` of `len` elements where the element at `i` is `f(i)`.
+    ///
+    /// If `f` panics, the elements built so far are dropped.
+    pub fn from_fn(len: usize, mut f: impl FnMut(usize) -> T) -> Self {
+        let mut vec = Self::with_capacity(len);
+        for i in 0..len {
+            // `push` never reallocates here, and unwinding drops `vec` with
+            // exactly the elements written so far.
+            vec.push(f(i));
+        }
+        vec
+    }
+
     pub fn push(&mut self, val: T) {
         if self.len == self.cap() {
             self.buf.grow();
@@ -40,6 +56,22 @@ impl<T> Vec<T> {
         self.len += 1;
     }
 
+    /// Like `push`, but hands `val` back along with the error if growing fails,
+    /// instead of panicking or aborting.
+    ///
+    /// # Errors
+    /// Fails like `try_reserve(1)`, leaving `self` unchanged.
+    pub fn try_push(&mut self, val: T) -> Result<(), (T, TryReserveError)> {
+        if self.len == self.cap() {
+            if let Err(err) = self.buf.try_reserve(1) {
+                return Err((val, err));
+            }
+        }
+        unsafe { self.buf.write(self.len, val) };
+        self.len += 1;
+        Ok(())
+    }
+
     pub fn pop(&mut self) -> Option<T> {
         if self.is_empty() {
             return None;
@@ -118,10 +150,362 @@ impl<T> Vec<T> {
             .unwrap_or_else(|_| panic!("index was {index} when len was {}", self.len))
     }
 
+    /// Swap-removes the element at `index` only if `pred` holds for it.
+    ///
+    /// # Panics
+    /// Panics if `index >= len`.
+    pub fn swap_remove_if(&mut self, index: usize, pred: impl FnOnce(&T) -> bool) -> Option<T> {
+        let Some(val) = self.get(index) else {
+            panic!("index was {index} when len was {}", self.len);
+        };
+        pred(val).then(|| self.swap_remove(index))
+    }
+
     /// Makes space for at least additional MORE elem while keeping exponential
     /// growth.
     pub fn reserve(&mut self, additional: usize) {
-        self.buf.reserve(additional);
+        let spare = self.cap() - self.len;
+        if additional > spare {
+            self.buf.reserve(additional - spare);
+        }
+    }
+
+    /// Moves `other`'s elements onto the end of `self` in reverse order, like
+    /// popping one stack onto another. `other` is left empty but keeps its
+    /// allocation.
+    pub fn append_reversed(&mut self, other: &mut Self) {
+        self.reserve(other.len);
+        while let Some(val) = other.pop() {
+            self.push(val);
+        }
+    }
+
+    /// Appends the items of `iter` in reverse order.
+    ///
+    /// Works for any iterator, not just double-ended ones: the items are
+    /// appended in order, and then the whole appended tail is reversed in a
+    /// second pass. Use `extend_rev_double_ended` to skip that pass.
+    pub fn extend_rev(&mut self, iter: impl IntoIterator<Item = T>) {
+        let start = self.len;
+        self.extend(iter);
+        self.as_slice_mut()[start..].reverse();
+    }
+
+    /// Like `extend_rev`, but pushes the items straight from `next_back`, after
+    /// reserving for the iterator's lower size bound.
+    pub fn extend_rev_double_ended<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        self.extend(iter.into_iter().rev());
+    }
+
+    /// Appends the items of `iter` until the first `Err`, which is returned.
+    /// Items pushed before the error stay in the vec.
+    ///
+    /// # Errors
+    /// Returns the first `Err` yielded by `iter`.
+    pub fn try_extend<E>(&mut self, iter: impl IntoIterator<Item = Result<T, E>>) -> Result<(), E> {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for val in iter {
+            self.push(val?);
+        }
+        Ok(())
+    }
+
+    /// Consumes the vec, moving its elements out in batches of `batch_size`,
+    /// except for the last batch, which may be smaller.
+    ///
+    /// # Panics
+    /// Panics if `batch_size == 0`.
+    pub fn into_batches(self, batch_size: usize) -> impl Iterator<Item = Self> {
+        assert!(batch_size != 0, "batch size must be non-zero");
+        let mut iter = self.into_iter();
+        core::iter::from_fn(move || {
+            (iter.len() != 0).then(|| iter.by_ref().take(batch_size).collect())
+        })
+    }
+
+    /// Splits the elements into `[T; N]` frames, returning an iterator over the
+    /// whole frames along with the trailing elements that don't fill one.
+    ///
+    /// # Panics
+    /// Panics if `N == 0`.
+    #[must_use]
+    pub fn frames<const N: usize>(
+        &self,
+    ) -> (impl DoubleEndedIterator<Item = &[T; N]> + ExactSizeIterator + '_, &[T]) {
+        assert!(N != 0, "frame size must be non-zero");
+        let (frames, rest) = self.as_slice().as_chunks::<N>();
+        (frames.iter(), rest)
+    }
+
+    /// Consumes the vec, moving its elements out as `[T; N]` arrays.
+    ///
+    /// # Panics
+    /// Panics if `N == 0`.
+    #[must_use]
+    pub fn into_chunks<const N: usize>(self) -> IntoChunks<T, N> {
+        assert!(N != 0, "chunk size must be non-zero");
+        IntoChunks { iter: self.into_iter() }
+    }
+
+    /// Drops every element past `len`. Does nothing if `len >= self.len()`.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = ptr::slice_from_raw_parts_mut(unsafe { self.ptr().add(len) }, self.len - len);
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    /// Keeps only the elements for which `f(index, &elem)` returns true, where
+    /// `index` is the element's position before any removal.
+    pub fn retain_indexed(&mut self, mut f: impl FnMut(usize, &T) -> bool) {
+        let mut kept = 0;
+        for index in 0..self.len {
+            if f(index, &self[index]) {
+                self.swap(kept, index);
+                kept += 1;
+            }
+        }
+        self.truncate(kept);
+    }
+
+    /// Yields each element along with its previous and next neighbours, which
+    /// are `None` at the ends.
+    #[must_use]
+    pub fn iter_context(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (Option<&T>, &T, Option<&T>)> + ExactSizeIterator + '_
+    {
+        self.iter().enumerate().map(|(index, val)| {
+            let prev = index.checked_sub(1).and_then(|prev| self.get(prev));
+            (prev, val, self.get(index + 1))
+        })
+    }
+
+    /// Calls `f` on each adjacent pair `(self[i], self[i + 1])`, front to back.
+    pub fn for_each_adjacent_mut(&mut self, mut f: impl FnMut(&mut T, &mut T)) {
+        for i in 1..self.len {
+            let (front, back) = self.split_at_mut(i);
+            f(&mut front[i - 1], &mut back[0]);
+        }
+    }
+
+    /// Splits the elements into consecutive batches whose weights sum to at
+    /// most `max_weight`. An element heavier than `max_weight` gets a batch of
+    /// its own, so every batch holds at least one element.
+    pub fn batch_by_weight<'a, F>(
+        &'a self,
+        max_weight: u64,
+        weight: F,
+    ) -> impl Iterator<Item = &'a [T]> + 'a
+    where
+        F: Fn(&T) -> u64 + 'a,
+    {
+        let mut rest = self.as_slice();
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let mut total = weight(&rest[0]);
+            let mut len = 1;
+            while let Some(next) = rest.get(len) {
+                total = total.saturating_add(weight(next));
+                if total > max_weight {
+                    break;
+                }
+                len += 1;
+            }
+            let (batch, tail) = rest.split_at(len);
+            rest = tail;
+            Some(batch)
+        })
+    }
+
+    /// Calls `f` on each overlapping window of `size` elements, front to back.
+    /// Nothing is called if `size > len`.
+    ///
+    /// # Panics
+    /// Panics if `size == 0`.
+    pub fn windows_mut(&mut self, size: usize, mut f: impl FnMut(&mut [T])) {
+        assert!(size != 0, "window size must be non-zero");
+        for start in 0..self.len.saturating_sub(size - 1) {
+            f(&mut self.as_slice_mut()[start..start + size]);
+        }
+    }
+
+    /// Splits the elements into `min(n, len)` disjoint chunks whose lengths
+    /// differ by at most one, longer chunks first, e.g. to hand out to scoped
+    /// threads.
+    ///
+    /// # Panics
+    /// Panics if `n == 0`.
+    pub fn split_into_n(&mut self, n: usize) -> Vec<&mut [T]> {
+        assert!(n != 0, "chunk count must be non-zero");
+        let chunks = n.min(self.len);
+        if chunks == 0 {
+            return Vec::new();
+        }
+        let (base, extra) = (self.len / chunks, self.len % chunks);
+        let mut rest = self.as_slice_mut();
+        Vec::from_fn(chunks, |index| {
+            let (chunk, tail) =
+                mem::take(&mut rest).split_at_mut(base + usize::from(index < extra));
+            rest = tail;
+            chunk
+        })
+    }
+
+    /// Removes consecutive elements with equal keys, keeping the first of each
+    /// run, and returns the removed elements in their original order.
+    #[must_use]
+    pub fn dedup_returning<K: PartialEq>(&mut self, mut key: impl FnMut(&T) -> K) -> Self {
+        let mut removed = Self::new();
+        let Some(first) = self.first() else { return removed };
+        let mut last_key = key(first);
+        self.coalesce(
+            |_, val| {
+                let val_key = key(val);
+                let same = val_key == last_key;
+                last_key = val_key;
+                same
+            },
+            |_, val| removed.push(val),
+        );
+        removed
+    }
+
+    /// Merges each run of consecutive elements that `same` considers equal
+    /// into its first element, by passing every later one to `merge`.
+    ///
+    /// Each element is compared against the merged element before it, not
+    /// against its original neighbour. Compacts in place without allocating.
+    pub fn coalesce(
+        &mut self,
+        mut same: impl FnMut(&T, &T) -> bool,
+        mut merge: impl FnMut(&mut T, T),
+    ) {
+        /// `[write + 1, read)` has been moved out. On drop, closes that gap by
+        /// moving `[read, len)` down, so a panic in `same` or `merge` leaves
+        /// every remaining element in the vec exactly once.
+        struct Gap<'a, T> {
+            vec: &'a mut Vec<T>,
+            write: usize,
+            read: usize,
+            len: usize,
+        }
+
+        impl<T> Drop for Gap<'_, T> {
+            fn drop(&mut self) {
+                let tail = self.len - self.read;
+                unsafe { self.vec.buf.shift(self.read, self.write + 1, tail) };
+                self.vec.len = self.write + 1 + tail;
+            }
+        }
+
+        let len = self.len;
+        if len < 2 {
+            return;
+        }
+        let ptr = self.ptr();
+        let mut gap = Gap { vec: self, write: 0, read: 1, len };
+        while gap.read < len {
+            let (prev, current) = unsafe { (ptr.add(gap.write), ptr.add(gap.read)) };
+            if same(unsafe { &*prev }, unsafe { &*current }) {
+                let val = unsafe { ptr::read(current) };
+                gap.read += 1;
+                merge(unsafe { &mut *prev }, val);
+            } else {
+                gap.write += 1;
+                unsafe { ptr::copy(current, ptr.add(gap.write), 1) };
+                gap.read += 1;
+            }
+        }
+    }
+
+    /// Shuffles the elements in place with a Fisher-Yates shuffle driven by a
+    /// small PRNG, so the same `seed` always gives the same permutation. Not
+    /// suitable where the order must be unpredictable.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn shuffle_with_seed(&mut self, seed: u64) {
+        let mut state = seed;
+        for i in (1..self.len).rev() {
+            // Maps the random bits onto `0..=i` without a division.
+            let j = ((u128::from(split_mix(&mut state)) * (i as u128 + 1)) >> 64) as usize;
+            self.swap(i, j);
+        }
+    }
+
+    /// Sorts by `f`, calling it exactly once per element.
+    ///
+    /// The keys are computed up front alongside each element's index, sorted,
+    /// and the resulting permutation is then applied in place.
+    pub fn sort_by_cached_key<K: Ord>(&mut self, f: impl FnMut(&T) -> K) {
+        let mut keys: Vec<_> = self.iter().map(f).enumerate().map(|(i, key)| (key, i)).collect();
+        // Indices are unique, so this is as stable as a stable sort.
+        keys.sort_unstable();
+        for i in 0..self.len {
+            // Elements before `i` were already swapped away, so follow the swaps
+            // to find the element's current position.
+            let mut index = keys[i].1;
+            while index < i {
+                index = keys[index].1;
+            }
+            keys[i].1 = index;
+            self.swap(i, index);
+        }
+    }
+
+    /// Stably sorts by `key` with an LSD radix sort, taking O(n) for each of
+    /// the key's 8 bytes. Bytes that are the same for every key are
+    /// skipped.
+    ///
+    /// Every key is computed before any element is moved, so if `key` panics
+    /// the vec is left unchanged.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn sort_by_u64_key(&mut self, key: impl Fn(&T) -> u64) {
+        let keys: Vec<_> = self.iter().map(key).collect();
+        let mut items: Vec<_> = keys.into_iter().zip(self.take()).collect();
+        let mut buckets: [Vec<(u64, T)>; 256] = core::array::from_fn(|_| Vec::new());
+        for shift in (0..u64::BITS).step_by(8) {
+            let byte = |key: u64| (key >> shift) as u8 as usize;
+            let Some(&(first, _)) = items.first() else { break };
+            if items.iter().all(|&(key, _)| byte(key) == byte(first)) {
+                continue;
+            }
+            // Moving through `IntoIter` and back keeps every allocation around
+            // for the next pass.
+            let mut iter = mem::take(&mut items).into_iter();
+            for item in iter.by_ref() {
+                buckets[byte(item.0)].push(item);
+            }
+            items = iter.into_vec();
+            for bucket in &mut buckets {
+                let mut iter = mem::take(bucket).into_iter();
+                items.extend(iter.by_ref());
+                *bucket = iter.into_vec();
+            }
+        }
+        self.extend(items.into_iter().map(|(_, val)| val));
+    }
+
+    /// Like `reserve`, but reports failure instead of panicking or aborting.
+    ///
+    /// # Errors
+    /// Returns `CapacityOverflow` if the new capacity would take more than
+    /// `isize::MAX` bytes, and `AllocError` if the allocator fails. `self` is
+    /// left unchanged in both cases.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let spare = self.cap() - self.len;
+        if additional > spare {
+            self.buf.try_reserve(additional - spare)?;
+        }
+        Ok(())
     }
 
     pub fn shrink_to_fit(&mut self) {
@@ -134,12 +518,306 @@ impl<T> Vec<T> {
         let mut vec = ManuallyDrop::new(self);
         unsafe { Box::from_raw(vec.as_slice_mut()) }
     }
+
+    /// Moves all elements into a new `Vec`, leaving `self` empty.
+    ///
+    /// Unlike `mem::take(&mut vec)`, which resets the capacity to zero, `self`
+    /// keeps its allocation so it can be refilled without reallocating.
+    #[must_use]
+    pub fn take(&mut self) -> Self {
+        let mut taken = Self::with_capacity(self.len);
+        unsafe { ptr::copy_nonoverlapping(self.ptr(), taken.ptr(), self.len) };
+        taken.len = mem::take(&mut self.len);
+        taken
+    }
+
+    /// Like `rotate_left` on the slice, but when the spare capacity can hold
+    /// the smaller of the two halves, that half is moved through the spare
+    /// space with a few `memcpy`s instead of being rotated in place.
+    ///
+    /// The buffered path copies the `min(mid, len - mid)` elements of the
+    /// smaller half out and back, and moves the rest with one `memmove`.
+    /// Without spare room, the in-place rotation has to fall back to
+    /// swapping elements around once the smaller half gets large, so the
+    /// buffered path should gain the most there. For a short smaller half
+    /// both are about equally cheap. No crossover point has been measured.
+    ///
+    /// # Panics
+    /// Panics if `mid > len`.
+    pub fn rotate_left_buffered(&mut self, mid: usize) {
+        assert!(mid <= self.len, "mid was {mid} when len was {}", self.len);
+        let (len, back) = (self.len, self.len - mid);
+        let spare = self.cap() - len;
+        // In the diagrams, `F` is the first `mid` elements and `B` the rest.
+        if mid <= back && mid <= spare {
+            // [F, B, _] -> [F, B, F] -> [B, F, _]
+            unsafe {
+                ptr::copy_nonoverlapping(self.ptr(), self.ptr().add(len), mid);
+                self.buf.shift(mid, 0, len);
+            }
+        } else if back <= spare {
+            // [F, B, _] -> [F, B, B] -> [_, F, B] -> [B, F, _]
+            unsafe {
+                ptr::copy_nonoverlapping(self.ptr().add(mid), self.ptr().add(len), back);
+                self.buf.shift(0, back, mid);
+                ptr::copy_nonoverlapping(self.ptr().add(len), self.ptr(), back);
+            }
+        } else {
+            self.as_slice_mut().rotate_left(mid);
+        }
+    }
+
+    /// A cursor starting at the first element.
+    pub const fn cursor_mut(&mut self) -> VecCursor<'_, T> {
+        VecCursor { vec: self, index: 0 }
+    }
+
+    /// Removes the first `n` elements (or all of them if `n > len`) and
+    /// returns them in order, shifting the rest down once.
+    #[must_use]
+    pub fn drain_front(&mut self, n: usize) -> Self {
+        let n = n.min(self.len);
+        let mut front = Self::with_capacity(n);
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr(), front.ptr(), n);
+            self.buf.shift(n, 0, self.len - n);
+        }
+        front.len = n;
+        self.len -= n;
+        front
+    }
+
+    /// Removes the elements in `range` and returns them in order, shifting
+    /// the rest down once.
+    ///
+    /// # Panics
+    /// Panics if the range starts after it ends or ends past `len`.
+    #[must_use]
+    pub fn remove_range(&mut self, range: impl RangeBounds<usize>) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).expect("range start overflowed"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).expect("range end overflowed"),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        assert!(start <= end, "range started at {start} but ended at {end}");
+        assert!(end <= self.len, "range ended at {end} when len was {}", self.len);
+
+        let mut removed = Self::with_capacity(end - start);
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr().add(start), removed.ptr(), end - start);
+            self.buf.shift(end, start, self.len - end);
+        }
+        removed.len = end - start;
+        self.len -= end - start;
+        removed
+    }
+}
+
+impl Vec<u8> {
+    /// Creates a buffer of `len` zeroed bytes, zeroing them with a single
+    /// `write_bytes` instead of pushing byte by byte. Any extra capacity is
+    /// left uninitialized.
+    #[must_use]
+    pub fn zeroed(len: usize) -> Self {
+        let mut vec = Self::with_capacity(len);
+        unsafe { ptr::write_bytes(vec.ptr(), 0, len) };
+        vec.len = len;
+        vec
+    }
+
+    /// # Errors
+    /// Returns the error from `core::str::from_utf8` if the bytes aren't valid
+    /// UTF-8.
+    pub fn as_str(&self) -> Result<&str, core::str::Utf8Error> {
+        core::str::from_utf8(self.as_slice())
+    }
+
+    /// # Safety
+    /// The bytes must be valid UTF-8.
+    #[must_use]
+    pub unsafe fn as_str_unchecked(&self) -> &str {
+        unsafe { core::str::from_utf8_unchecked(self.as_slice()) }
+    }
+}
+
+impl<T> Vec<T>
+where
+    T: Clone,
+{
+    pub fn cloned_iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.iter().cloned()
+    }
+
+    /// Appends `n` copies of `val`, growing at most once.
+    pub fn push_n(&mut self, val: T, n: usize) {
+        if n == 0 {
+            return;
+        }
+        self.reserve(n);
+        for _ in 1..n {
+            self.push(val.clone());
+        }
+        self.push(val);
+    }
+
+    /// Endlessly yields clones of the elements, starting over after the last
+    /// one. Yields nothing if the vec is empty.
+    pub fn into_iter_cycle(self) -> impl Iterator<Item = T> {
+        let mut index = 0;
+        core::iter::from_fn(move || {
+            let val = self.get(index)?.clone();
+            index = (index + 1) % self.len;
+            Some(val)
+        })
+    }
+}
+
+impl<T> Vec<T>
+where
+    T: PartialEq,
+{
+    /// The number of maximal runs of consecutive equal elements, which is the
+    /// length `dedup` would leave.
+    #[must_use]
+    pub fn count_runs(&self) -> usize {
+        let changes = self.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        usize::from(!self.is_empty()) + changes
+    }
+}
+
+impl<T> Vec<T>
+where
+    T: Hash + Eq,
+{
+    /// Removes all but the last occurrence of each distinct element, keeping
+    /// the survivors in their original order.
+    ///
+    /// For example `[1, 2, 1, 3, 2]` becomes `[1, 3, 2]`: the first `1` and
+    /// `2` are dropped because each appears again later.
+    pub fn dedup_keep_last(&mut self) {
+        let mut seen = HashSet::new();
+        let mut keep: Vec<_> = self.iter().rev().map(|val| seen.insert(val).is_none()).collect();
+        drop(seen);
+        keep.as_slice_mut().reverse();
+        self.retain_indexed(|index, _| keep[index]);
+    }
+
+    /// Compares the two vecs as multisets: true if they hold the same elements
+    /// the same number of times, in any order. Takes O(len) on average.
+    #[must_use]
+    pub fn eq_ignore_order(&self, other: &Self) -> bool {
+        if self.len != other.len {
+            return false;
+        }
+        let mut counts = HashMap::with_capacity(self.len);
+        for val in self {
+            *counts.entry(val).or_insert(0_usize) += 1;
+        }
+        other.iter().all(|val| match counts.get_mut(val) {
+            Some(count) if *count != 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        })
+    }
+}
+
+impl<T> Vec<T>
+where
+    T: Ord,
+{
+    /// Inserts `val` into an already sorted vec, after any equal elements, and
+    /// returns the index it ended up at.
+    pub fn binary_insert(&mut self, val: T) -> usize {
+        let index = self.partition_point(|other| *other <= val);
+        self.insert(index, val);
+        index
+    }
+
+    /// Swap-removes the smallest element in O(n). If several are equally
+    /// small, the first one is removed.
+    pub fn extract_min(&mut self) -> Option<T> {
+        let index = self.iter().enumerate().min_by(|a, b| a.1.cmp(b.1))?.0;
+        Some(self.swap_remove(index))
+    }
+
+    /// Swap-removes the largest element in O(n). If several are equally
+    /// large, the last one is removed.
+    pub fn extract_max(&mut self) -> Option<T> {
+        let index = self.iter().enumerate().max_by(|a, b| a.1.cmp(b.1))?.0;
+        Some(self.swap_remove(index))
+    }
+
+    /// Reorders the vec with quickselect so that the element at `n` is the one
+    /// sorting would put there, with no larger elements before it and no
+    /// smaller ones after it. Takes O(len) on average.
+    ///
+    /// # Panics
+    /// Panics if `n >= self.len()`.
+    pub fn select_nth(&mut self, n: usize) -> &mut T {
+        assert!(n < self.len, "index was {n} when len was {}", self.len);
+        let (mut lo, mut hi) = (0, self.len);
+        while hi - lo > 1 {
+            let pivot = self.median_of_three(lo, lo + (hi - lo) / 2, hi - 1);
+            self.swap(lo, pivot);
+            // Three-way partition, so runs of equal elements can't make this
+            // quadratic: `[lo, lt)` is less than the pivot at `lt`, `[lt, i)` is
+            // equal to it and `[gt, hi)` is greater.
+            let (mut lt, mut i, mut gt) = (lo, lo + 1, hi);
+            while i < gt {
+                match self[i].cmp(&self[lt]) {
+                    Ordering::Less => {
+                        self.swap(lt, i);
+                        lt += 1;
+                        i += 1;
+                    }
+                    Ordering::Equal => i += 1,
+                    Ordering::Greater => {
+                        gt -= 1;
+                        self.swap(i, gt);
+                    }
+                }
+            }
+            if n < lt {
+                hi = lt;
+            } else if n >= gt {
+                lo = gt;
+            } else {
+                break;
+            }
+        }
+        &mut self[n]
+    }
+
+    fn median_of_three(&self, a: usize, b: usize, c: usize) -> usize {
+        if (self[a] < self[b]) == (self[b] < self[c]) {
+            b
+        } else if (self[b] < self[a]) == (self[a] < self[c]) {
+            a
+        } else {
+            c
+        }
+    }
 }
 
 impl<T> Extend<T> for Vec<T> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // Reserving a small upper bound wastes little space, and avoids
+        // growing repeatedly for iterators like `filter` with a lower bound of
+        // 0.
+        const SMALL_EXTEND: usize = 16;
         let iter = iter.into_iter();
-        self.reserve(iter.size_hint().0);
+        let additional = match iter.size_hint() {
+            (_, Some(upper)) if upper <= SMALL_EXTEND => upper,
+            (lower, _) => lower,
+        };
+        self.reserve(additional);
         for val in iter {
             self.push(val);
         }
@@ -176,6 +854,48 @@ impl<T> Vec<T> {
     pub fn as_slice_mut(&mut self) -> &mut [T] {
         self
     }
+
+    #[must_use]
+    pub fn first(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+
+    #[must_use]
+    pub fn last(&self) -> Option<&T> {
+        self.as_slice().last()
+    }
+
+    #[must_use]
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        self.as_slice_mut().first_mut()
+    }
+
+    #[must_use]
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.as_slice_mut().last_mut()
+    }
+}
+
+impl<T> Drop for Vec<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_slice_mut()) };
+    }
+}
+
+impl<T> Vec<T> {
+    /// Reinterprets the elements as `U`s, delegating to
+    /// `<[T]>::align_to_mut`.
+    ///
+    /// Only the middle slice is viewed as `U`. The prefix holds the elements
+    /// before the first `U`-aligned address, and the suffix holds those that
+    /// don't fill a whole `U`; either may be empty.
+    ///
+    /// # Safety
+    /// Same as `<[T]>::align_to_mut`: every bit pattern written through any of
+    /// the slices must be valid for both `T` and `U`.
+    pub unsafe fn align_to_mut<U>(&mut self) -> (&mut [T], &mut [U], &mut [T]) {
+        unsafe { self.as_slice_mut().align_to_mut() }
+    }
 }
 
 impl<T> Default for Vec<T> {
@@ -309,9 +1029,22 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
         if self.current == self.end {
             return None;
         }
-        let val = unsafe { self.buf.read(self.end) };
         self.end -= 1;
-        Some(val)
+        Some(unsafe { self.buf.read(self.end) })
+    }
+
+    fn rfold<B, F>(mut self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let mut acc = init;
+        // If `f` panics, `end` already excludes the moved out element, so
+        // `Drop` only drops the ones still left.
+        while self.current != self.end {
+            self.end -= 1;
+            acc = f(acc, unsafe { self.buf.read(self.end) });
+        }
+        acc
     }
 }
 
@@ -323,7 +1056,129 @@ impl<T> ExactSizeIterator for IntoIter<T> {
 
 impl<T> Drop for IntoIter<T> {
     fn drop(&mut self) {
-        for _ in self {}
+        let remaining = ptr::slice_from_raw_parts_mut(
+            unsafe { self.buf.ptr.as_ptr().add(self.current) },
+            self.len(),
+        );
+        self.current = self.end;
+        // `drop_in_place` keeps going past a panicking element, and `buf`'s own
+        // drop then frees the allocation either way.
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+impl<T> IntoIter<T> {
+    /// Collects the remaining elements back into a `Vec`, reusing the original
+    /// allocation.
+    #[must_use]
+    pub fn into_vec(mut self) -> Vec<T> {
+        let len = self.len();
+        let mut buf = mem::take(&mut self.buf);
+        unsafe { buf.shift(self.current, 0, len) };
+        self.end = self.current;
+        Vec { buf, len }
+    }
+
+    /// Collects the remaining elements into a `Vec` in reverse order, reusing
+    /// the original allocation.
+    #[must_use]
+    pub fn rev_into_vec(self) -> Vec<T> {
+        let mut vec = self.into_vec();
+        vec.reverse();
+        vec
+    }
+}
+
+/// Yields a `Vec`'s elements as `[T; N]` arrays. The trailing elements that
+/// don't fill a whole array are kept until `into_remainder`, or dropped along
+/// with the iterator.
+pub struct IntoChunks<T, const N: usize> {
+    iter: IntoIter<T>,
+}
+
+impl<T, const N: usize> IntoChunks<T, N> {
+    /// The remaining elements, which after exhausting the iterator are the
+    /// last `len % N`.
+    #[must_use]
+    pub fn into_remainder(self) -> Vec<T> {
+        self.iter.into_vec()
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoChunks<T, N> {
+    type Item = [T; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.iter.len() < N {
+            return None;
+        }
+        let iter = &mut self.iter;
+        let chunk = unsafe { iter.buf.ptr.as_ptr().add(iter.current).cast::<[T; N]>().read() };
+        iter.current += N;
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.iter.len() / N;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoChunks<T, N> {}
+
+/// A position in a `Vec` that can move back and forth and edit around itself.
+///
+/// The position ranges from `0` to `len`, where `len` is an empty end position
+/// just past the last element.
+pub struct VecCursor<'a, T> {
+    vec: &'a mut Vec<T>,
+    index: usize,
+}
+
+impl<T> VecCursor<'_, T> {
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The element under the cursor, or `None` at the end position.
+    #[must_use]
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.vec.get_mut(self.index)
+    }
+
+    /// Moves one element forward, stopping at the end position.
+    pub const fn move_next(&mut self) {
+        if self.index < self.vec.len {
+            self.index += 1;
+        }
+    }
+
+    /// Moves one element back, stopping at the first element.
+    pub const fn move_prev(&mut self) {
+        self.index = self.index.saturating_sub(1);
+    }
+
+    /// Inserts `val` before the cursor, which stays on the same element.
+    pub fn insert_before(&mut self, val: T) {
+        self.vec.insert(self.index, val);
+        self.index += 1;
+    }
+
+    /// Inserts `val` after the current element, leaving the cursor where it
+    /// is. At the end position this appends `val` and stays at the new end,
+    /// like `insert_before`.
+    pub fn insert_after(&mut self, val: T) {
+        if self.index == self.vec.len {
+            return self.insert_before(val);
+        }
+        self.vec.insert(self.index + 1, val);
+    }
+
+    /// Removes the current element, after which the cursor points at the one
+    /// that followed it. Returns `None` at the end position.
+    pub fn remove(&mut self) -> Option<T> {
+        self.vec.try_remove(self.index).ok()
     }
 }
 
@@ -335,6 +1190,15 @@ impl<T> FromIterator<T> for Vec<T> {
     }
 }
 
+/// `SplitMix64`: tiny and fast, with good enough statistics for shuffling.
+const fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
 #[test]
 fn test_iters() {
     use alloc::string::String;
@@ -354,6 +1218,41 @@ fn test_iters() {
     assert!(items.into_iter().eq(strings));
 }
 
+#[test]
+fn test_into_iter_next_back() {
+    use alloc::rc::Rc;
+
+    let items: Vec<_> = (0..5).collect();
+    assert!(items.into_iter().rev().eq((0..5).rev()));
+
+    let val = Rc::new(());
+    let items = Vec::from_fn(4, |_| val.clone());
+    let mut iter = items.into_iter();
+    while let Some(back) = iter.next_back() {
+        assert!(Rc::ptr_eq(&back, &val));
+    }
+    assert_eq!(Rc::strong_count(&val), 1);
+}
+
+#[test]
+fn test_drop_elements() {
+    use core::cell::Cell;
+
+    struct CountDrop<'a>(&'a Cell<usize>);
+    impl Drop for CountDrop<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let drops = Cell::new(0);
+    let mut items = Vec::from_fn(10, |_| CountDrop(&drops));
+    drop(items.pop());
+    assert_eq!(drops.get(), 1);
+    drop(items);
+    assert_eq!(drops.get(), 10);
+}
+
 #[test]
 fn test_insert_remove() {
     let mut items = Vec::new();
@@ -373,3 +1272,805 @@ fn test_boxed_slice() {
     let post = pre.clone().into_boxed_slice().into();
     assert_eq!(pre, post);
 }
+
+#[test]
+fn test_take() {
+    let mut items: Vec<_> = (0..10).collect();
+    let cap = items.cap();
+
+    let taken = items.take();
+    assert!(items.is_empty());
+    assert_eq!(items.cap(), cap);
+    assert!(taken.iter().copied().eq(0..10));
+
+    items.extend(10..15);
+    assert_eq!(items.cap(), cap);
+    assert!(items.iter().copied().eq(10..15));
+}
+
+#[test]
+fn test_reserve_counts_from_len() {
+    let mut items = Vec::with_capacity(16);
+    items.extend(0..4);
+    let allocations = crate::test_alloc::count_allocations(|| items.reserve(12));
+    assert_eq!(allocations, 0);
+    assert_eq!(items.cap(), 16);
+
+    let allocations = crate::test_alloc::count_allocations(|| items.reserve(13));
+    assert_eq!(allocations, 1);
+    assert!(items.cap() >= 17);
+}
+
+#[test]
+fn test_zeroed() {
+    let buf = Vec::zeroed(4096);
+    assert_eq!(buf.len(), 4096);
+    assert!(buf.cap() >= buf.len());
+    assert!(buf.iter().all(|&byte| byte == 0));
+
+    assert!(Vec::zeroed(0).is_empty());
+}
+
+#[test]
+fn test_drain_front() {
+    let mut items: Vec<_> = (0..10).collect();
+
+    let front = items.drain_front(3);
+    assert!(front.iter().copied().eq(0..3));
+    assert!(items.iter().copied().eq(3..10));
+
+    let rest = items.drain_front(100);
+    assert!(rest.iter().copied().eq(3..10));
+    assert!(items.is_empty());
+    assert!(items.drain_front(1).is_empty());
+}
+
+#[test]
+fn test_retain_indexed() {
+    use alloc::rc::Rc;
+    let marker = Rc::new(());
+    let mut items: Vec<_> = (0..10).map(|i| (i, Rc::clone(&marker))).collect();
+
+    items.retain_indexed(|index, _| index % 2 == 0);
+    assert!(items.iter().map(|item| item.0).eq([0, 2, 4, 6, 8]));
+    assert_eq!(Rc::strong_count(&marker), 6);
+
+    items.retain_indexed(|index, item| index != 1 && item.0 != 8);
+    assert!(items.iter().map(|item| item.0).eq([0, 4, 6]));
+    assert_eq!(Rc::strong_count(&marker), 4);
+
+    drop(items);
+    assert_eq!(Rc::strong_count(&marker), 1);
+}
+
+#[test]
+fn test_append_reversed() {
+    let mut items = Vec::new();
+    items.push(9);
+    let mut other: Vec<_> = [1, 2, 3].into_iter().collect();
+    let cap = other.cap();
+
+    items.append_reversed(&mut other);
+    assert!(items.iter().eq(&[9, 3, 2, 1]));
+    assert!(other.is_empty());
+    assert_eq!(other.cap(), cap);
+}
+
+#[test]
+fn test_into_chunks() {
+    use alloc::{rc::Rc, string::ToString};
+
+    let items: Vec<_> = (0..10).map(|i| i.to_string()).collect();
+    let mut chunks = items.into_chunks::<4>();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks.next(), Some(["0", "1", "2", "3"].map(ToString::to_string)));
+    assert_eq!(chunks.next(), Some(["4", "5", "6", "7"].map(ToString::to_string)));
+    assert_eq!(chunks.next(), None);
+    let remainder = chunks.into_remainder();
+    assert!(remainder.iter().eq(["8", "9"]));
+
+    let marker = Rc::new(());
+    let items: Vec<_> = (0..10).map(|_| Rc::clone(&marker)).collect();
+    let mut chunks = items.into_chunks::<4>();
+    drop(chunks.next());
+    assert_eq!(Rc::strong_count(&marker), 7);
+    drop(chunks);
+    assert_eq!(Rc::strong_count(&marker), 1);
+}
+
+#[test]
+fn test_into_iter_into_vec() {
+    let items: Vec<_> = (0..10).collect();
+    let cap = items.cap();
+    let mut iter = items.into_iter();
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(9));
+    assert_eq!(iter.next_back(), Some(8));
+
+    let rest = iter.into_vec();
+    assert!(rest.iter().copied().eq(1..8));
+    assert_eq!(rest.cap(), cap);
+}
+
+#[test]
+fn test_swap_remove_if() {
+    let mut items: Vec<_> = (0..5).collect();
+
+    assert_eq!(items.swap_remove_if(1, |&x| x == 1), Some(1));
+    assert!(items.iter().eq(&[0, 4, 2, 3]));
+
+    assert_eq!(items.swap_remove_if(1, |&x| x == 1), None);
+    assert!(items.iter().eq(&[0, 4, 2, 3]));
+
+    assert_eq!(items.swap_remove_if(3, |_| true), Some(3));
+    assert!(items.iter().eq(&[0, 4, 2]));
+}
+
+#[test]
+#[should_panic = "index was 3 when len was 3"]
+fn test_swap_remove_if_out_of_bounds() {
+    let mut items: Vec<_> = (0..3).collect();
+    items.swap_remove_if(3, |_| true);
+}
+
+#[test]
+fn test_try_reserve() {
+    use alloc::alloc::Layout;
+
+    let mut items: Vec<u64> = (0..4).collect();
+    assert_eq!(items.try_reserve(100), Ok(()));
+    assert!(items.cap() >= 104);
+
+    assert_eq!(items.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+    assert_eq!(items.try_reserve(isize::MAX as usize / 8), Err(TryReserveError::CapacityOverflow));
+
+    let additional = items.cap() * 4;
+    let result = crate::test_alloc::with_failing_allocations(|| items.try_reserve(additional));
+    let layout = Layout::array::<u64>(items.len() + additional).unwrap();
+    assert_eq!(result, Err(TryReserveError::AllocError { layout }));
+    assert!(items.iter().copied().eq(0..4));
+}
+
+#[test]
+fn test_cycle() {
+    let items: Vec<_> = (1..=3).collect();
+    assert!(items.cloned_iter().eq(1..=3));
+    assert!(items.into_iter_cycle().take(7).eq([1, 2, 3, 1, 2, 3, 1]));
+    assert_eq!(Vec::<i32>::new().into_iter_cycle().next(), None);
+}
+
+#[test]
+fn test_sort_by_cached_key() {
+    use alloc::string::String;
+
+    let words = ["lorem", "ipsum", "do", "sit", "a", "consectetur", "elit"];
+    let mut items: Vec<_> = words.map(String::from).into_iter().collect();
+    let mut calls = 0;
+    items.sort_by_cached_key(|word| {
+        calls += 1;
+        word.len()
+    });
+    assert_eq!(calls, words.len());
+    assert!(items.iter().eq(["a", "do", "sit", "elit", "lorem", "ipsum", "consectetur"]));
+
+    let mut items: Vec<_> = (0..100).rev().collect();
+    items.sort_by_cached_key(|&i| i % 10);
+    let mut expected: alloc::vec::Vec<_> = (0..100).rev().collect();
+    expected.sort_by_key(|&i| i % 10);
+    assert!(items.iter().eq(&expected));
+}
+
+#[test]
+fn test_dedup_returning() {
+    let mut items: Vec<_> = [(1, 'a'), (1, 'b'), (2, 'c')].into_iter().collect();
+    let removed = items.dedup_returning(|item| item.0);
+    assert!(items.iter().eq(&[(1, 'a'), (2, 'c')]));
+    assert!(removed.iter().eq(&[(1, 'b')]));
+
+    let mut items: Vec<_> = [1, 1, 1, 2, 1, 3, 3].into_iter().collect();
+    let removed = items.dedup_returning(|&item| item);
+    assert!(items.iter().eq(&[1, 2, 1, 3]));
+    assert!(removed.iter().eq(&[1, 1, 3]));
+
+    let mut items: Vec<_> = [1, 2, 3].into_iter().collect();
+    let allocations = crate::test_alloc::count_allocations(|| {
+        let removed = items.dedup_returning(|&item| item);
+        assert!(removed.is_empty());
+    });
+    assert_eq!(allocations, 0);
+    assert!(Vec::<i32>::new().dedup_returning(|&item| item).is_empty());
+}
+
+#[test]
+fn test_align_to_mut() {
+    let mut bytes = Vec::zeroed(16);
+    let (prefix, words, suffix) = unsafe { bytes.align_to_mut::<u32>() };
+    assert!(words.len() >= 3);
+    assert_eq!(prefix.len() + words.len() * 4 + suffix.len(), 16);
+
+    words.fill(u32::MAX);
+    let (prefix, suffix) = (prefix.len(), suffix.len());
+    assert!(bytes.as_slice()[prefix..16 - suffix].iter().all(|&byte| byte == u8::MAX));
+}
+
+#[test]
+fn test_for_each_adjacent_mut() {
+    let mut items: Vec<_> = [1, 2, 3, 4].into_iter().collect();
+    let mut calls = 0;
+    items.for_each_adjacent_mut(|a, b| {
+        mem::swap(a, b);
+        calls += 1;
+    });
+    assert_eq!(items.as_slice(), &[2, 3, 4, 1]);
+    assert_eq!(calls, 3);
+
+    let mut items: Vec<_> = core::iter::once(5).collect();
+    items.for_each_adjacent_mut(|_, _| unreachable!());
+    Vec::<i32>::new().for_each_adjacent_mut(|_, _| unreachable!());
+}
+
+#[test]
+fn test_from_fn() {
+    use alloc::rc::Rc;
+    use std::panic::{self, AssertUnwindSafe};
+
+    let squares = Vec::from_fn(10, |i| i * i);
+    assert!(squares.iter().copied().eq((0..10).map(|i| i * i)));
+    assert_eq!(squares.cap(), 10);
+    assert!(Vec::from_fn(0, |_| -> i32 { unreachable!() }).is_empty());
+
+    let marker = Rc::new(());
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        Vec::from_fn(10, |i| {
+            assert!(i != 5, "boom");
+            Rc::clone(&marker)
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(Rc::strong_count(&marker), 1);
+}
+
+#[test]
+fn test_into_batches() {
+    use alloc::rc::Rc;
+
+    let items: Vec<_> = (0..10).collect();
+    let batches: Vec<_> = items.into_batches(3).collect();
+    assert!(batches.iter().map(Vec::len).eq([3, 3, 3, 1]));
+    assert!(batches.iter().take(3).all(|batch| (3..6).contains(&batch.cap())));
+    assert!(batches.iter().flat_map(|batch| batch.iter().copied()).eq(0..10));
+
+    assert_eq!(Vec::<i32>::new().into_batches(3).count(), 0);
+
+    let marker = Rc::new(());
+    let items: Vec<_> = (0..10).map(|_| Rc::clone(&marker)).collect();
+    let mut batches = items.into_batches(4);
+    drop(batches.next());
+    assert_eq!(Rc::strong_count(&marker), 7);
+    drop(batches);
+    assert_eq!(Rc::strong_count(&marker), 1);
+}
+
+#[test]
+#[should_panic = "batch size must be non-zero"]
+fn test_into_batches_zero() {
+    let _ = Vec::<i32>::new().into_batches(0);
+}
+
+#[test]
+fn test_as_str() {
+    let text: Vec<_> = "héllo".bytes().collect();
+    assert_eq!(text.as_str(), Ok("héllo"));
+    assert_eq!(unsafe { text.as_str_unchecked() }, "héllo");
+
+    let invalid: Vec<_> = [b'a', 0xff, b'b'].into_iter().collect();
+    let err = invalid.as_str().unwrap_err();
+    assert_eq!(err.valid_up_to(), 1);
+
+    assert_eq!(Vec::new().as_str(), Ok(""));
+}
+
+#[test]
+fn test_extend_rev() {
+    let mut items: Vec<_> = core::iter::once(10).collect();
+    items.extend_rev((0..5).rev());
+    assert_eq!(items.as_slice(), &[10, 0, 1, 2, 3, 4]);
+
+    let mut next = 1;
+    items.extend_rev(core::iter::from_fn(|| {
+        next *= 2;
+        (next <= 16).then_some(next)
+    }));
+    assert_eq!(items.as_slice(), &[10, 0, 1, 2, 3, 4, 16, 8, 4, 2]);
+
+    items.extend_rev(core::iter::empty());
+    assert_eq!(items.len(), 10);
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn test_windows_mut() {
+    let mut reference: [f32; 8] = core::array::from_fn(|i| (i * i) as f32);
+    let mut samples: Vec<_> = reference.into_iter().collect();
+
+    samples.windows_mut(3, |window| window[1] = (window[0] + window[1] + window[2]) / 3.0);
+    for i in 1..reference.len() - 1 {
+        reference[i] = (reference[i - 1] + reference[i] + reference[i + 1]) / 3.0;
+    }
+    assert_eq!(samples.as_slice(), &reference);
+
+    let mut calls = 0;
+    samples.windows_mut(8, |_| calls += 1);
+    samples.windows_mut(9, |_| calls += 1);
+    assert_eq!(calls, 1);
+}
+
+#[test]
+#[should_panic = "window size must be non-zero"]
+fn test_windows_mut_zero() {
+    Vec::<i32>::new().windows_mut(0, |_| {});
+}
+
+#[test]
+fn test_sort_by_u64_key() {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut items: Vec<_> = (0..10_000)
+        .map(|index| {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            (((state >> 33) % 1000) | ((state % 3) << 56), index)
+        })
+        .collect();
+    let mut reference: alloc::vec::Vec<_> = items.iter().copied().collect();
+    reference.sort_by_key(|&(key, _)| key);
+
+    items.sort_by_u64_key(|&(key, _)| key);
+    assert_eq!(items.as_slice(), reference.as_slice());
+
+    let mut small: Vec<_> =
+        [(3, 'a'), (1, 'b'), (3, 'c'), (0, 'd'), (1, 'e')].into_iter().collect();
+    small.sort_by_u64_key(|&(key, _)| key);
+    assert!(small.iter().map(|&(_, val)| val).eq(['d', 'b', 'e', 'a', 'c']));
+    Vec::<u64>::new().sort_by_u64_key(|&key| key);
+}
+
+#[test]
+fn test_first_last() {
+    let mut items = Vec::<i32>::new();
+    assert_eq!(items.first(), None);
+    assert_eq!(items.last(), None);
+    assert_eq!(items.first_mut(), None);
+    assert_eq!(items.last_mut(), None);
+
+    items.push(1);
+    assert_eq!(items.first(), Some(&1));
+    assert_eq!(items.last(), Some(&1));
+    *items.first_mut().unwrap() += 1;
+    *items.last_mut().unwrap() *= 10;
+    assert_eq!(items.as_slice(), &[20]);
+
+    items.extend([3, 4, 5]);
+    assert_eq!(items.first(), Some(&20));
+    assert_eq!(items.last(), Some(&5));
+    *items.first_mut().unwrap() = 0;
+    *items.last_mut().unwrap() = 9;
+    assert_eq!(items.as_slice(), &[0, 3, 4, 9]);
+}
+
+#[test]
+fn test_dedup_keep_last() {
+    let mut items: Vec<_> = [1, 2, 1, 3, 2].into_iter().collect();
+    items.dedup_keep_last();
+    assert_eq!(items.as_slice(), &[1, 3, 2]);
+
+    let mut items: Vec<_> = ["a", "a", "b", "a"].into_iter().collect();
+    items.dedup_keep_last();
+    assert_eq!(items.as_slice(), &["b", "a"]);
+
+    let mut items: Vec<_> = (0..10).collect();
+    items.dedup_keep_last();
+    assert!(items.iter().copied().eq(0..10));
+
+    let mut items = Vec::<i32>::new();
+    items.dedup_keep_last();
+    assert!(items.is_empty());
+}
+
+#[test]
+fn test_binary_insert() {
+    let mut items = Vec::new();
+    assert_eq!(items.binary_insert((5, 'a')), 0);
+    assert_eq!(items.binary_insert((1, 'a')), 0);
+    assert_eq!(items.binary_insert((9, 'a')), 2);
+    assert_eq!(items.binary_insert((5, 'b')), 2);
+    assert_eq!(items.binary_insert((5, 'c')), 3);
+    assert_eq!(items.as_slice(), &[(1, 'a'), (5, 'a'), (5, 'b'), (5, 'c'), (9, 'a')]);
+
+    let mut items: Vec<_> = [1, 3, 3, 7].into_iter().collect();
+    assert_eq!(items.binary_insert(3), 3);
+    assert_eq!(items.binary_insert(2), 1);
+    assert_eq!(items.binary_insert(0), 0);
+    assert_eq!(items.binary_insert(8), 7);
+    assert_eq!(items.as_slice(), &[0, 1, 2, 3, 3, 3, 7, 8]);
+}
+
+#[test]
+fn test_remove_range() {
+    let mut items: Vec<_> = (0..10).collect();
+    let removed = items.remove_range(3..6);
+    assert_eq!(removed.as_slice(), &[3, 4, 5]);
+    assert_eq!(items.as_slice(), &[0, 1, 2, 6, 7, 8, 9]);
+
+    assert_eq!(items.remove_range(5..=6).as_slice(), &[8, 9]);
+    assert!(items.remove_range(2..2).is_empty());
+    assert_eq!(items.len(), 5);
+
+    let removed = items.remove_range(..);
+    assert_eq!(removed.as_slice(), &[0, 1, 2, 6, 7]);
+    assert!(items.is_empty());
+}
+
+#[test]
+#[should_panic = "range ended at 4 when len was 3"]
+fn test_remove_range_out_of_bounds() {
+    let mut items: Vec<_> = (0..3).collect();
+    let _ = items.remove_range(1..4);
+}
+
+#[test]
+fn test_extract_min_max() {
+    let mut items: Vec<_> = [7, 3, 9, 1, 8, 1, 4].into_iter().collect();
+    assert_eq!(items.extract_min(), Some(1));
+    assert_eq!(items.extract_max(), Some(9));
+    assert_eq!(items.len(), 5);
+    let mut rest: Vec<_> = items.iter().copied().collect();
+    rest.sort_unstable();
+    assert_eq!(rest.as_slice(), &[1, 3, 4, 7, 8]);
+
+    let smallest: Vec<_> = core::iter::from_fn(|| items.extract_min()).take(3).collect();
+    assert_eq!(smallest.as_slice(), &[1, 3, 4]);
+    assert_eq!(items.len(), 2);
+
+    assert_eq!(Vec::<i32>::new().extract_min(), None);
+    assert_eq!(Vec::<i32>::new().extract_max(), None);
+}
+
+#[test]
+fn test_cursor_mut() {
+    let mut items: Vec<_> = (0..5).collect();
+    let mut cursor = items.cursor_mut();
+    cursor.move_prev();
+    assert_eq!(cursor.index(), 0);
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 2));
+
+    cursor.insert_before(10);
+    cursor.insert_after(11);
+    assert_eq!(cursor.index(), 3);
+    assert_eq!(cursor.current(), Some(&mut 2));
+    assert_eq!(cursor.remove(), Some(2));
+    assert_eq!(cursor.current(), Some(&mut 11));
+    *cursor.current().unwrap() += 1;
+
+    for _ in 0..10 {
+        cursor.move_next();
+    }
+    assert_eq!(cursor.index(), 6);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.remove(), None);
+    cursor.insert_after(20);
+    cursor.insert_before(21);
+    assert_eq!(cursor.index(), 8);
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(&mut 21));
+
+    assert_eq!(items.as_slice(), &[0, 1, 10, 12, 3, 4, 20, 21]);
+}
+
+#[test]
+fn test_rotate_left_buffered() {
+    use alloc::string::{String, ToString};
+
+    for len in [0, 1, 2, 7, 10] {
+        for mid in 0..=len {
+            let mut reference: alloc::vec::Vec<_> = (0..len).map(|i| i.to_string()).collect();
+            reference.rotate_left(mid);
+
+            let mut full: Vec<String> = (0..len).map(|i| i.to_string()).collect();
+            full.buf.resize(len);
+            full.rotate_left_buffered(mid);
+            assert_eq!(full.as_slice(), reference.as_slice(), "len {len}, mid {mid}");
+
+            let mut spare = Vec::with_capacity(len + len / 2);
+            spare.extend((0..len).map(|i| i.to_string()));
+            spare.rotate_left_buffered(mid);
+            assert_eq!(spare.as_slice(), reference.as_slice(), "len {len}, mid {mid}");
+        }
+    }
+}
+
+#[test]
+#[should_panic = "mid was 4 when len was 3"]
+fn test_rotate_left_buffered_out_of_bounds() {
+    let mut items: Vec<_> = (0..3).collect();
+    items.rotate_left_buffered(4);
+}
+
+#[test]
+fn test_batch_by_weight() {
+    let items: Vec<u64> = [3, 3, 5, 2].into_iter().collect();
+    let batches: Vec<_> = items.batch_by_weight(6, |&weight| weight).collect();
+    assert_eq!(batches.as_slice(), &[&[3, 3][..], &[5], &[2]]);
+
+    let items: Vec<u64> = [9, 1, 1, 8, 1].into_iter().collect();
+    let batches: Vec<_> = items.batch_by_weight(6, |&weight| weight).collect();
+    assert_eq!(batches.as_slice(), &[&[9][..], &[1, 1], &[8], &[1]]);
+
+    assert_eq!(items.batch_by_weight(100, |_| 0).count(), 1);
+    assert_eq!(Vec::<u64>::new().batch_by_weight(6, |&weight| weight).count(), 0);
+}
+
+#[test]
+fn test_coalesce() {
+    use alloc::string::String;
+
+    let mut items: Vec<_> = [(1, "a"), (1, "b"), (2, "c"), (1, "d")]
+        .map(|(key, val)| (key, String::from(val)))
+        .into_iter()
+        .collect();
+    items.coalesce(|a, b| a.0 == b.0, |into, (_, val)| into.1.push_str(&val));
+    assert_eq!(items.len(), 3);
+    assert_eq!((items[0].0, items[0].1.as_str()), (1, "ab"));
+    assert_eq!((items[1].0, items[1].1.as_str()), (2, "c"));
+    assert_eq!((items[2].0, items[2].1.as_str()), (1, "d"));
+
+    let mut sums: Vec<_> = [(1, 1), (1, 2), (1, 3), (2, 4), (3, 5), (3, 6)].into_iter().collect();
+    sums.coalesce(|a, b| a.0 == b.0, |into, (_, val)| into.1 += val);
+    assert_eq!(sums.as_slice(), &[(1, 6), (2, 4), (3, 11)]);
+
+    let mut runs: Vec<_> = [1, 1, 2, 2, 2, 3, 1, 1].into_iter().collect();
+    let allocations = crate::test_alloc::count_allocations(|| {
+        runs.coalesce(|a, b| a == b, |_, _| {});
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(runs.as_slice(), &[1, 2, 3, 1]);
+}
+
+#[test]
+fn test_frames() {
+    let items: Vec<_> = (0..10).collect();
+    let (frames, rest) = items.frames::<4>();
+    assert_eq!(frames.len(), 2);
+    assert!(frames.eq([&[0, 1, 2, 3], &[4, 5, 6, 7]]));
+    assert_eq!(rest, &[8, 9]);
+
+    let (frames, rest) = items.frames::<5>();
+    assert_eq!(frames.count(), 2);
+    assert!(rest.is_empty());
+
+    let (mut frames, rest) = items.frames::<11>();
+    assert!(frames.next().is_none());
+    assert_eq!(rest.len(), 10);
+}
+
+#[test]
+fn test_shuffle_with_seed() {
+    let shuffled = |seed| {
+        let mut items: Vec<_> = (0..100).collect();
+        items.shuffle_with_seed(seed);
+        items
+    };
+    assert_eq!(shuffled(7), shuffled(7));
+    assert_ne!(shuffled(7), shuffled(8));
+    assert!(!shuffled(7).iter().copied().eq(0..100));
+
+    let mut sorted = shuffled(7);
+    sorted.sort_unstable();
+    assert!(sorted.iter().copied().eq(0..100));
+
+    let mut single: Vec<_> = core::iter::once(1).collect();
+    single.shuffle_with_seed(3);
+    assert_eq!(single.as_slice(), &[1]);
+    Vec::<i32>::new().shuffle_with_seed(3);
+}
+
+#[test]
+fn test_try_extend() {
+    let mut items: Vec<_> = (0..3).collect();
+    assert_eq!(items.try_extend([Ok(3), Ok(4)]), Ok::<_, &str>(()));
+    assert_eq!(items.as_slice(), &[0, 1, 2, 3, 4]);
+
+    let result = items.try_extend([Ok(5), Err("six"), Ok(7), Err("eight")]);
+    assert_eq!(result, Err("six"));
+    assert_eq!(items.as_slice(), &[0, 1, 2, 3, 4, 5]);
+
+    assert_eq!(items.try_extend(core::iter::empty::<Result<_, ()>>()), Ok(()));
+    assert_eq!(items.len(), 6);
+}
+
+#[test]
+fn test_push_n() {
+    let mut items = Vec::new();
+    let allocations = crate::test_alloc::count_allocations(|| items.push_n(7, 1000));
+    assert_eq!(allocations, 1);
+    assert_eq!(items.len(), 1000);
+    assert!(items.iter().all(|&val| val == 7));
+
+    items.push_n(8, 0);
+    assert_eq!(items.len(), 1000);
+
+    let val = alloc::rc::Rc::new(());
+    let mut rcs = Vec::new();
+    rcs.push_n(val.clone(), 3);
+    assert_eq!(alloc::rc::Rc::strong_count(&val), 4);
+}
+
+#[test]
+fn test_extend_small_upper_bound() {
+    let mut items = Vec::new();
+    let allocations = crate::test_alloc::count_allocations(|| {
+        items.extend((0..16).filter(|val| val % 3 != 0));
+    });
+    assert_eq!(allocations, 1);
+    assert!(items.iter().copied().eq((0..16).filter(|val| val % 3 != 0)));
+}
+
+#[test]
+fn test_rev_into_vec() {
+    let mut iter = (0..10).collect::<Vec<_>>().into_iter();
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(9));
+    let ptr = iter.buf.ptr;
+    let rev = iter.rev_into_vec();
+    assert_eq!(rev.as_slice(), &[8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(rev.buf.ptr, ptr);
+
+    let mut iter = rev.into_iter();
+    iter.next();
+    assert_eq!(
+        iter.rfold(Vec::new(), |mut acc, val| {
+            acc.push(val);
+            acc
+        })
+        .as_slice(),
+        &[1, 2, 3, 4, 5, 6, 7]
+    );
+    assert!(Vec::<i32>::new().into_iter().rev_into_vec().is_empty());
+}
+
+#[test]
+fn test_iter_context() {
+    let items: Vec<_> = (1..=3).collect();
+    let mut iter = items.iter_context();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some((None, &1, Some(&2))));
+    assert_eq!(iter.next(), Some((Some(&1), &2, Some(&3))));
+    assert_eq!(iter.next(), Some((Some(&2), &3, None)));
+    assert_eq!(iter.next(), None);
+
+    let single: Vec<_> = core::iter::once(7).collect();
+    assert!(single.iter_context().eq([(None, &7, None)]));
+    assert_eq!(Vec::<i32>::new().iter_context().next(), None);
+}
+
+#[test]
+fn test_try_push() {
+    use alloc::{alloc::Layout, string::String};
+
+    let mut items = Vec::new();
+    assert_eq!(items.try_push(String::from("first")), Ok(()));
+    // Pushing into spare capacity doesn't allocate, so only the growth fails.
+    let (pushed, val, err) = crate::test_alloc::with_failing_allocations(|| {
+        let mut pushed = 1;
+        loop {
+            match items.try_push(String::new()) {
+                Ok(()) => pushed += 1,
+                Err((val, err)) => break (pushed, val, err),
+            }
+        }
+    });
+    assert_eq!(pushed, 4);
+    assert_eq!(val, "");
+    assert_eq!(err, TryReserveError::AllocError { layout: Layout::array::<String>(8).unwrap() });
+    assert_eq!(items.len(), 4);
+    assert_eq!(items[0], "first");
+
+    assert_eq!(items.try_push(String::from("last")), Ok(()));
+    assert_eq!(items[4], "last");
+}
+
+#[test]
+fn test_count_runs() {
+    assert_eq!(Vec::<i32>::new().count_runs(), 0);
+    assert_eq!(Vec::from_fn(5, |_| 1).count_runs(), 1);
+    assert_eq!((0..5).collect::<Vec<_>>().count_runs(), 5);
+    assert_eq!([1, 1, 2, 3, 3].into_iter().collect::<Vec<_>>().count_runs(), 3);
+    assert_eq!([1, 2, 1, 1, 2].into_iter().collect::<Vec<_>>().count_runs(), 4);
+}
+
+#[test]
+fn test_select_nth() {
+    let mut items: Vec<_> = (0..101).collect();
+    items.shuffle_with_seed(7);
+    assert_eq!(*items.select_nth(50), 50);
+    assert!(items.as_slice()[..50].iter().all(|&val| val < 50));
+    assert!(items.as_slice()[51..].iter().all(|&val| val > 50));
+
+    for n in [0, 13, 100] {
+        items.shuffle_with_seed(n as u64);
+        assert_eq!(*items.select_nth(n), n);
+    }
+
+    let mut repeated = Vec::from_fn(64, |index| index % 4);
+    repeated.shuffle_with_seed(3);
+    assert_eq!(*repeated.select_nth(40), 2);
+    assert!(repeated.as_slice()[..40].iter().all(|&val| val <= 2));
+    assert!(repeated.as_slice()[41..].iter().all(|&val| val >= 2));
+
+    *repeated.select_nth(0) = 9;
+    assert_eq!(repeated[0], 9);
+}
+
+#[test]
+#[should_panic = "index was 3 when len was 3"]
+fn test_select_nth_out_of_bounds() {
+    let mut items: Vec<_> = (0..3).collect();
+    items.select_nth(3);
+}
+
+#[test]
+fn test_split_into_n() {
+    let mut items: Vec<_> = (0..10).collect();
+    let chunks = items.split_into_n(3);
+    assert!(chunks.iter().map(|chunk| chunk.len()).eq([4, 3, 3]));
+    assert!(chunks.iter().flat_map(|chunk| chunk.iter()).copied().eq(0..10));
+    drop(chunks);
+
+    std::thread::scope(|scope| {
+        for chunk in items.split_into_n(4) {
+            scope.spawn(|| chunk.iter_mut().for_each(|val| *val *= 2));
+        }
+    });
+    assert!(items.iter().copied().eq((0..10).map(|val| val * 2)));
+
+    assert!(items.split_into_n(20).iter().all(|chunk| chunk.len() == 1));
+    assert_eq!(items.split_into_n(20).len(), 10);
+    assert!(Vec::<i32>::new().split_into_n(3).is_empty());
+}
+
+#[test]
+#[should_panic = "chunk count must be non-zero"]
+fn test_split_into_zero() {
+    let _ = Vec::<i32>::new().split_into_n(0);
+}
+
+#[test]
+fn test_eq_ignore_order() {
+    let items = |vals: &[i32]| vals.iter().copied().collect::<Vec<_>>();
+    assert!(items(&[1, 2, 2, 3]).eq_ignore_order(&items(&[2, 3, 1, 2])));
+    assert!(Vec::<i32>::new().eq_ignore_order(&Vec::new()));
+    assert!(!items(&[1, 2, 2, 3]).eq_ignore_order(&items(&[1, 2, 3, 3])));
+    assert!(!items(&[1, 2, 2]).eq_ignore_order(&items(&[2, 1])));
+    assert!(!items(&[1]).eq_ignore_order(&items(&[2])));
+}
+
+#[test]
+fn test_extend_rev_double_ended() {
+    let mut items: Vec<_> = (0..3).collect();
+    let allocations = crate::test_alloc::count_allocations(|| {
+        items.extend_rev_double_ended(3..100);
+    });
+    assert_eq!(allocations, 1);
+    assert!(items.as_slice()[..3].iter().copied().eq(0..3));
+    assert!(items.as_slice()[3..].iter().copied().eq((3..100).rev()));
+
+    let mut calls = Vec::new();
+    let mut items = Vec::new();
+    items.extend_rev_double_ended((0..3).map(|val| {
+        calls.push(val);
+        val
+    }));
+    // Produced back to front, rather than in order and reversed afterwards.
+    assert_eq!(calls.as_slice(), &[2, 1, 0]);
+    assert_eq!(items.as_slice(), &[2, 1, 0]);
+}